@@ -0,0 +1,396 @@
+use std::cmp::Ordering;
+
+use crate::random::Random;
+use crate::solution::Solution;
+
+pub trait Acceptance {
+    /// Name of the acceptance criterion.
+    fn name(&self) -> String;
+    /// Returns true if the candidate solution replaces the current solution. Called once per
+    /// iteration, so criteria can keep state like a temperature across iterations. Criteria that
+    /// measure how much worse a solution is should use `deciding_values`, so that they also work
+    /// with `ObjectiveMode::Lexicographic`.
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        best: &Solution,
+        random: &mut Random,
+    ) -> bool;
+}
+
+/// Values that decide the order of two solutions. Solutions valued in
+/// `ObjectiveMode::Lexicographic` are decided by the first level on which they differ, so a
+/// worse solution is only worse by its difference on that level, however much it gains on later
+/// levels. Otherwise the solutions' values decide.
+#[must_use]
+pub fn deciding_values(a: &Solution, b: &Solution) -> (f64, f64) {
+    match (a.levels(), b.levels()) {
+        (Some(a), Some(b)) => a
+            .iter()
+            .zip(b)
+            .find(|(x, y)| x.total_cmp(y).is_ne())
+            .or_else(|| a.iter().zip(b).next())
+            .map_or((0.0, 0.0), |(&x, &y)| (x, y)),
+        _ => (a.value(), b.value()),
+    }
+}
+
+/// Values a solution is compared on, level by level.
+fn levels(solution: &Solution) -> Vec<f64> {
+    solution
+        .levels()
+        .map_or_else(|| vec![solution.value()], <[f64]>::to_vec)
+}
+
+fn compare_levels(a: &[f64], b: &[f64]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.total_cmp(y))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Only accepts solutions that improve the current solution.
+#[derive(Default)]
+pub struct GreedyAcceptance;
+
+impl Acceptance for GreedyAcceptance {
+    fn name(&self) -> String {
+        String::from("greedy")
+    }
+
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        _best: &Solution,
+        _random: &mut Random,
+    ) -> bool {
+        candidate.compare(current).is_lt()
+    }
+}
+
+/// Accepts improvements, and worse solutions with probability `exp(-delta / temperature)`. The
+/// temperature starts at `initial_temperature` and is multiplied by `cooling_rate` every
+/// iteration. In lexicographic mode `delta` is the difference on the deciding level.
+pub struct SimulatedAnnealingAcceptance {
+    pub initial_temperature: f64,
+    pub cooling_rate: f64,
+    temperature: Option<f64>,
+}
+
+impl SimulatedAnnealingAcceptance {
+    #[must_use]
+    pub fn new(initial_temperature: f64, cooling_rate: f64) -> Self {
+        Self {
+            initial_temperature,
+            cooling_rate,
+            temperature: None,
+        }
+    }
+
+    /// Temperature of the next iteration.
+    #[must_use]
+    pub fn temperature(&self) -> f64 {
+        self.temperature.unwrap_or(self.initial_temperature)
+    }
+}
+
+impl Default for SimulatedAnnealingAcceptance {
+    fn default() -> Self {
+        Self::new(100.0, 0.99)
+    }
+}
+
+impl Acceptance for SimulatedAnnealingAcceptance {
+    fn name(&self) -> String {
+        String::from("simulated_annealing")
+    }
+
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        _best: &Solution,
+        random: &mut Random,
+    ) -> bool {
+        let temperature = self.temperature();
+        self.temperature = Some(temperature * self.cooling_rate);
+        if candidate.compare(current).is_lt() {
+            return true;
+        }
+        let (candidate, current) = deciding_values(candidate, current);
+        let delta = candidate - current;
+        temperature > 0.0 && random.f64() < (-delta / temperature).exp()
+    }
+}
+
+/// Accepts solutions whose value deviates from the best solution's value by at most `deviation`,
+/// relative to the best value. In lexicographic mode the values are those of the deciding level.
+pub struct RecordToRecordAcceptance {
+    pub deviation: f64,
+}
+
+impl Default for RecordToRecordAcceptance {
+    fn default() -> Self {
+        Self { deviation: 0.01 }
+    }
+}
+
+impl Acceptance for RecordToRecordAcceptance {
+    fn name(&self) -> String {
+        String::from("record_to_record")
+    }
+
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        best: &Solution,
+        _random: &mut Random,
+    ) -> bool {
+        if candidate.compare(current).is_lt() {
+            return true;
+        }
+        let (candidate, best) = deciding_values(candidate, best);
+        candidate <= best + self.deviation * best.abs()
+    }
+}
+
+/// Accepts solutions that are worse than the current solution by less than a threshold. The
+/// threshold starts at `initial_threshold` and is multiplied by `decay` every iteration. In
+/// lexicographic mode the difference is taken on the deciding level.
+pub struct ThresholdAcceptance {
+    pub initial_threshold: f64,
+    pub decay: f64,
+    threshold: Option<f64>,
+}
+
+impl ThresholdAcceptance {
+    #[must_use]
+    pub fn new(initial_threshold: f64, decay: f64) -> Self {
+        Self {
+            initial_threshold,
+            decay,
+            threshold: None,
+        }
+    }
+
+    /// Threshold of the next iteration.
+    #[must_use]
+    pub fn threshold(&self) -> f64 {
+        self.threshold.unwrap_or(self.initial_threshold)
+    }
+}
+
+impl Default for ThresholdAcceptance {
+    fn default() -> Self {
+        Self::new(10.0, 0.99)
+    }
+}
+
+impl Acceptance for ThresholdAcceptance {
+    fn name(&self) -> String {
+        String::from("threshold")
+    }
+
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        _best: &Solution,
+        _random: &mut Random,
+    ) -> bool {
+        let threshold = self.threshold();
+        self.threshold = Some(threshold * self.decay);
+        let (candidate_value, current_value) = deciding_values(candidate, current);
+        candidate.compare(current).is_lt() || candidate_value - current_value < threshold
+    }
+}
+
+/// Late acceptance hill climbing (LAHC). Accepts solutions that are no worse than the current
+/// solution, or than the current solution of `length` iterations ago. Solutions are compared as
+/// by `Solution::compare`, so lexicographic mode compares them level by level.
+pub struct LateAcceptance {
+    pub length: usize,
+    history: Vec<Vec<f64>>,
+    index: usize,
+}
+
+impl LateAcceptance {
+    #[must_use]
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            history: Vec::new(),
+            index: 0,
+        }
+    }
+}
+
+impl Default for LateAcceptance {
+    fn default() -> Self {
+        Self::new(50)
+    }
+}
+
+impl Acceptance for LateAcceptance {
+    fn name(&self) -> String {
+        String::from("late_acceptance")
+    }
+
+    fn accept(
+        &mut self,
+        candidate: &Solution,
+        current: &Solution,
+        _best: &Solution,
+        _random: &mut Random,
+    ) -> bool {
+        if self.history.is_empty() {
+            self.history = vec![levels(current); self.length.max(1)];
+            self.index = 0;
+        }
+        let accepted = candidate.compare(current).is_le()
+            || compare_levels(&levels(candidate), &self.history[self.index]).is_le();
+        self.history[self.index] = levels(if accepted { candidate } else { current });
+        self.index = (self.index + 1) % self.history.len();
+        accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DistanceMatrix, Location, Model, ModelBuilder, StopBuilder, Vehicle};
+    use crate::objective::{ObjectiveMode, TravelDistanceObjective, UnplannedObjective};
+    use crate::solution::Plan;
+
+    fn model() -> Model {
+        let stop = |id| {
+            StopBuilder::new(id, Location::new(id, 0.0, 0.0), vec![])
+                .unplanned_penalty(1.0)
+                .build()
+        };
+        ModelBuilder::new()
+            .stop(stop(0))
+            .stop(stop(1))
+            .stop(stop(2))
+            .stop(stop(3))
+            .vehicle(Vehicle::new(0, vec![]))
+            .objective(UnplannedObjective)
+            .build()
+    }
+
+    /// Solution valued by its number of unplanned stops.
+    fn solution(model: &Model, unplanned: usize) -> Solution {
+        let mut plan = Plan::new();
+        for unit in 0..4 - unplanned {
+            plan.insert(unit, 0, vec![unit]);
+        }
+        Solution::new().plan(model, &plan).unwrap()
+    }
+
+    #[test]
+    fn test_greedy_acceptance() {
+        let model = model();
+        let (good, bad) = (solution(&model, 1), solution(&model, 2));
+        let mut random = Random::seed(1);
+        let mut acceptance = GreedyAcceptance;
+        assert!(acceptance.accept(&good, &bad, &good, &mut random));
+        assert!(!acceptance.accept(&bad, &good, &good, &mut random));
+        assert!(!acceptance.accept(&good, &good, &good, &mut random));
+    }
+
+    #[test]
+    fn test_simulated_annealing_acceptance() {
+        let model = model();
+        let (good, bad) = (solution(&model, 0), solution(&model, 4));
+        let mut random = Random::seed(1);
+        let mut hot = SimulatedAnnealingAcceptance::new(1e9, 0.5);
+        assert!(hot.accept(&bad, &good, &good, &mut random));
+        assert!((hot.temperature() - 5e8).abs() < 1e-6);
+
+        let mut cold = SimulatedAnnealingAcceptance::new(1e-9, 0.5);
+        assert!(!cold.accept(&bad, &good, &good, &mut random));
+        assert!(cold.accept(&good, &bad, &good, &mut random));
+    }
+
+    #[test]
+    fn test_record_to_record_acceptance() {
+        let model = model();
+        let best = solution(&model, 2);
+        let (near, far) = (solution(&model, 3), solution(&model, 4));
+        let mut random = Random::seed(1);
+        let mut acceptance = RecordToRecordAcceptance { deviation: 0.5 };
+        assert!(acceptance.accept(&near, &far, &best, &mut random));
+        assert!(!acceptance.accept(&far, &near, &best, &mut random));
+    }
+
+    #[test]
+    fn test_threshold_acceptance() {
+        let model = model();
+        let (current, worse) = (solution(&model, 1), solution(&model, 2));
+        let mut random = Random::seed(1);
+        let mut acceptance = ThresholdAcceptance::new(1.5, 0.5);
+        assert!(acceptance.accept(&worse, &current, &current, &mut random));
+        assert!(!acceptance.accept(&worse, &current, &current, &mut random));
+    }
+
+    #[test]
+    fn test_lexicographic_acceptance() {
+        let stop = |id| {
+            StopBuilder::new(id, Location::new(id, 0.0, 0.0), vec![])
+                .unplanned_penalty(1.0)
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(stop(0))
+            .stop(stop(1))
+            .vehicle(Vehicle::new(0, vec![]))
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 1000.0],
+                vec![1000.0, 0.0],
+            ]))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .objective_mode(ObjectiveMode::Lexicographic)
+            .build();
+        let routed = |units: &[usize]| {
+            let mut plan = Plan::new();
+            for (position, &unit) in units.iter().enumerate() {
+                plan.insert(unit, 0, vec![position]);
+            }
+            Solution::new().plan(&model, &plan).unwrap()
+        };
+        // One more unplanned stop outweighs any travel saved, even though the weighted sum of
+        // the shorter solution is lower.
+        let (current, shorter) = (routed(&[0, 1]), routed(&[0]));
+        assert!(shorter.value() < current.value());
+        assert_eq!(deciding_values(&shorter, &current), (1.0, 0.0));
+
+        let mut random = Random::seed(1);
+        let mut threshold = ThresholdAcceptance::new(0.5, 1.0);
+        assert!(!threshold.accept(&shorter, &current, &current, &mut random));
+        let mut annealing = SimulatedAnnealingAcceptance::new(1e-9, 1.0);
+        assert!(!annealing.accept(&shorter, &current, &current, &mut random));
+        let mut record = RecordToRecordAcceptance { deviation: 0.01 };
+        assert!(!record.accept(&shorter, &current, &current, &mut random));
+        let mut late = LateAcceptance::new(1);
+        assert!(!late.accept(&shorter, &current, &current, &mut random));
+    }
+
+    #[test]
+    fn test_late_acceptance() {
+        let model = model();
+        let values: Vec<Solution> = (0..5).map(|u| solution(&model, u)).collect();
+        let mut random = Random::seed(1);
+        let mut acceptance = LateAcceptance::new(2);
+        // History starts as [3, 3].
+        assert!(acceptance.accept(&values[2], &values[3], &values[2], &mut random));
+        assert!(!acceptance.accept(&values[4], &values[2], &values[2], &mut random));
+        // History is now [2, 2], so a worse solution than the late one is rejected.
+        assert!(!acceptance.accept(&values[3], &values[2], &values[2], &mut random));
+        assert!(acceptance.accept(&values[2], &values[2], &values[2], &mut random));
+    }
+}
@@ -1,28 +1,53 @@
-use crate::solution::Plan;
+use crate::model::Model;
+use crate::solution::{Plan, Solution, SolutionVehicle};
 
 pub trait Constraint {
     /// Name of the constraint.
     fn name(&self) -> String;
-    /// Checks if the plan violates the constraint.
-    fn is_feasible(&self, plan: &Plan) -> bool;
+    /// Checks if the plan violates the constraint. The solution is the result of applying the plan.
+    fn is_feasible(&self, model: &Model, solution: &Solution, plan: &Plan) -> bool;
     /// Indicates if the constraint is temporal.
     fn is_temporal(&self) -> bool {
         false
     }
+    /// Indicates if the constraint only depends on each route by itself, so that a plan can be
+    /// checked with `is_route_feasible` on the routes it changes.
+    fn is_route_local(&self) -> bool {
+        false
+    }
+    /// Checks if a single route, already scheduled, violates the constraint. Only used if the
+    /// constraint is route-local.
+    fn is_route_feasible(&self, _model: &Model, _route: &SolutionVehicle) -> bool {
+        true
+    }
+    /// Indicates if a route that violates the constraint keeps violating it when more stops are
+    /// inserted, so that insertions can stop placing stops of a plan unit as soon as the route
+    /// is infeasible. Only used if the constraint is route-local.
+    fn is_monotone(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Default)]
 pub struct Constraints(Vec<Box<dyn Constraint>>);
 
 impl Constraints {
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
     pub fn get(&self, index: usize) -> Option<&dyn Constraint> {
         self.0.get(index).map(AsRef::as_ref)
     }
 
+    #[must_use]
     pub fn first(&self) -> Option<&dyn Constraint> {
         self.0.first().map(AsRef::as_ref)
     }
@@ -30,13 +55,30 @@ impl Constraints {
     pub fn push(&mut self, constraint: Box<dyn Constraint>) {
         self.0.push(constraint);
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Box<dyn Constraint>> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Constraints {
+    type Item = &'a Box<dyn Constraint>;
+    type IntoIter = std::slice::Iter<'a, Box<dyn Constraint>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
+/// Requires vehicles to be compatible with the stops they visit. A vehicle is compatible with a
+/// stop if it has every compatibility attribute the stop requires and the optional
+/// `StopCompatibilities` table does not exclude the pair.
 pub struct VehicleCompatibilityConstraint {
     compatible: StopCompatibilities,
 }
 
 impl VehicleCompatibilityConstraint {
+    #[must_use]
     pub fn new(compatible: StopCompatibilities) -> Self {
         Self { compatible }
     }
@@ -55,18 +97,257 @@ impl Constraint for VehicleCompatibilityConstraint {
         String::from("vehicle_compatibility")
     }
 
-    fn is_feasible(&self, plan: &Plan) -> bool {
-        self.compatible
-            .is_compatible(plan.stop().id, plan.vehicle().id)
+    fn is_feasible(&self, model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+        solution
+            .vehicles()
+            .iter()
+            .all(|v| self.is_route_feasible(model, v))
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn is_route_feasible(&self, model: &Model, route: &SolutionVehicle) -> bool {
+        route.route.iter().all(|s| {
+            self.compatible.is_compatible(s.stop, route.index)
+                && model
+                    .stops()
+                    .get(s.stop)
+                    .zip(model.vehicles().get(route.index))
+                    .is_some_and(|(stop, vehicle)| vehicle.is_compatible(stop))
+        })
+    }
+}
+
+/// Requires every route to stay within its vehicle's capacity in every dimension.
+///
+/// Stop quantities change the load of the vehicle when visited: positive quantities are picked up
+/// and negative quantities are delivered. A vehicle starts loaded with whatever it delivers before
+/// picking it up on the route. Missing capacity dimensions are unlimited and missing quantities
+/// are zero.
+#[derive(Default)]
+pub struct CapacityConstraint;
+
+impl CapacityConstraint {
+    /// Whether the route, given by stop indices, fits in the vehicle.
+    #[must_use]
+    pub fn fits(model: &Model, vehicle: usize, route: &[usize]) -> bool {
+        let Some(vehicle) = model.vehicles().get(vehicle) else {
+            return true;
+        };
+        vehicle
+            .capacity()
+            .iter()
+            .enumerate()
+            .all(|(dimension, &capacity)| {
+                let (mut load, mut lowest, mut highest) = (0.0_f64, 0.0_f64, 0.0_f64);
+                for &stop in route {
+                    load += model
+                        .stops()
+                        .get(stop)
+                        .and_then(|s| s.quantities().get(dimension))
+                        .copied()
+                        .unwrap_or(0.0);
+                    lowest = lowest.min(load);
+                    highest = highest.max(load);
+                }
+                highest - lowest <= capacity
+            })
+    }
+}
+
+impl Constraint for CapacityConstraint {
+    fn name(&self) -> String {
+        String::from("capacity")
+    }
+
+    fn is_feasible(&self, model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+        solution
+            .vehicles()
+            .iter()
+            .all(|v| self.is_route_feasible(model, v))
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_route_feasible(&self, model: &Model, route: &SolutionVehicle) -> bool {
+        let stops: Vec<usize> = route.route.iter().map(|s| s.stop).collect();
+        Self::fits(model, route.index, &stops)
+    }
+}
+
+/// Requires service at every routed stop to start within one of its time windows.
+#[derive(Default)]
+pub struct TimeWindowConstraint;
+
+impl Constraint for TimeWindowConstraint {
+    fn name(&self) -> String {
+        String::from("time_windows")
+    }
+
+    fn is_feasible(&self, model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+        solution
+            .vehicles()
+            .iter()
+            .all(|v| self.is_route_feasible(model, v))
+    }
+
+    fn is_temporal(&self) -> bool {
+        true
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn is_route_feasible(&self, model: &Model, route: &SolutionVehicle) -> bool {
+        route.route.iter().all(|s| {
+            model.stops().get(s.stop).is_some_and(|stop| {
+                stop.time_windows().is_empty()
+                    || stop
+                        .time_windows()
+                        .iter()
+                        .any(|w| w.start() <= s.start && s.start <= w.end())
+            })
+        })
+    }
+}
+
+/// Requires every route to end within its vehicle's shift, and to take at most its maximum route
+/// duration from departure, which floats to the first stop's time window, to the end of the route.
+#[derive(Default)]
+pub struct VehicleShiftConstraint;
+
+impl Constraint for VehicleShiftConstraint {
+    fn name(&self) -> String {
+        String::from("vehicle_shift")
+    }
+
+    fn is_feasible(&self, model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+        solution
+            .vehicles()
+            .iter()
+            .all(|v| self.is_route_feasible(model, v))
+    }
+
+    fn is_temporal(&self) -> bool {
+        true
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn is_route_feasible(&self, model: &Model, route: &SolutionVehicle) -> bool {
+        model.vehicles().get(route.index).is_none_or(|vehicle| {
+            vehicle.shift_end().is_none_or(|end| route.end <= end)
+                && vehicle
+                    .max_duration()
+                    .is_none_or(|duration| route.end - route.start <= duration)
+        })
     }
 }
 
 pub struct StopCompatibilities(Vec<Vec<bool>>);
 
 impl StopCompatibilities {
+    #[must_use]
+    pub fn new(compatible: Vec<Vec<bool>>) -> Self {
+        Self(compatible)
+    }
+
+    #[must_use]
     pub fn is_compatible(&self, stop_index: usize, vehicle_index: usize) -> bool {
         self.0
             .get(stop_index)
-            .is_some_and(|v| v.get(vehicle_index).copied().unwrap_or(true))
+            .is_none_or(|v| v.get(vehicle_index).copied().unwrap_or(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        CompatibilityAttribute, Location, ModelBuilder, Stop, StopBuilder, Vehicle, VehicleBuilder,
+    };
+
+    #[test]
+    fn test_vehicle_compatibility_attributes() {
+        let attribute = |key, value: &str| CompatibilityAttribute::new(key, value.to_string());
+        let model = ModelBuilder::new()
+            .stop(
+                StopBuilder::new(0, Location::new(0, 0.0, 0.0), vec![])
+                    .compatibility_attribute(attribute(0, "refrigerated"))
+                    .build(),
+            )
+            .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+            .vehicle(Vehicle::new(0, vec![]))
+            .vehicle(
+                VehicleBuilder::new(1, vec![])
+                    .compatibility_attribute(attribute(0, "refrigerated"))
+                    .compatibility_attribute(attribute(1, "lift"))
+                    .build(),
+            )
+            .build();
+        let routed = |plan_unit, vehicle| {
+            let mut plan = Plan::new();
+            plan.insert(plan_unit, vehicle, vec![0]);
+            Solution::new().plan(&model, &plan).unwrap()
+        };
+        let constraint = VehicleCompatibilityConstraint::default();
+        let feasible = |plan_unit, vehicle| {
+            constraint.is_feasible(&model, &routed(plan_unit, vehicle), &Plan::new())
+        };
+        assert!(!feasible(0, 0));
+        assert!(feasible(0, 1));
+        assert!(feasible(1, 0));
+        assert!(feasible(1, 1));
+
+        let constraint =
+            VehicleCompatibilityConstraint::new(StopCompatibilities::new(vec![vec![true, false]]));
+        assert!(!constraint.is_feasible(&model, &routed(0, 1), &Plan::new()));
+    }
+
+    #[test]
+    fn test_capacity_fits() {
+        let stop = |id, quantities| Stop::new(id, Location::new(id, 0.0, 0.0), quantities);
+        let model = ModelBuilder::new()
+            .stop(stop(0, vec![3.0, 1.0]))
+            .stop(stop(1, vec![-3.0, -1.0]))
+            .stop(stop(2, vec![4.0, 0.0]))
+            .stop(stop(3, vec![-5.0]))
+            .vehicle(Vehicle::new(0, vec![5.0, 1.0]))
+            .build();
+        assert!(CapacityConstraint::fits(&model, 0, &[0, 1]));
+        assert!(CapacityConstraint::fits(&model, 0, &[0, 1, 2]));
+        assert!(!CapacityConstraint::fits(&model, 0, &[0, 2, 1]));
+        assert!(CapacityConstraint::fits(&model, 0, &[3]));
+        assert!(CapacityConstraint::fits(&model, 0, &[3, 2]));
+        assert!(!CapacityConstraint::fits(&model, 0, &[2, 0]));
+        assert!(!CapacityConstraint::fits(&model, 0, &[0, 0]));
+    }
+
+    #[test]
+    fn test_stop_compatibilities() {
+        let compatible = StopCompatibilities::new(vec![vec![true, false]]);
+        assert!(compatible.is_compatible(0, 0));
+        assert!(!compatible.is_compatible(0, 1));
+        assert!(compatible.is_compatible(0, 2));
+        assert!(compatible.is_compatible(1, 1));
     }
 }
@@ -2,15 +2,23 @@
 //!
 //! A vehicle routing solver library.
 //!
-//! ! WARNING: Some APIs like the Plan API are still unimplemented and subject to change.
+//! ! WARNING: Some APIs are still unimplemented and subject to change.
 //!
 //! Supported:
 //!
 //! - Pickup and Delivery Problem (PDP)
 //!
+//! Pickups and deliveries are declared with `ModelBuilder::pickup_delivery` or, for longer chains
+//! of stops, `ModelBuilder::sequence`. Sequenced stops form a single plan unit that the solver
+//! assigns to one vehicle in the declared order. Sequences that share a stop merge into one plan
+//! unit that only enforces the declared orders, so two pickups for one delivery can be visited in
+//! either order.
+//!
 //! ```rust,ignore
-//! use solver_vrp::model::{Constraint, Model, ModelBuilder, Objective};
-//! use solver_vrp::operator::Operator;
+//! use solver_vrp::constraint::{CapacityConstraint, Constraint};
+//! use solver_vrp::model::{Model, ModelBuilder};
+//! use solver_vrp::objective::Objective;
+//! use solver_vrp::operator::{Operator, OperatorKind};
 //! use solver_vrp::solution::{Plan, Solution};
 //! use solver_vrp::solver::{Solver, SolverBuilder, SolverOptions};
 //! use solver_vrp::random::Random;
@@ -32,16 +40,17 @@
 //! }
 //!
 //! // Implement a custom constraint to enforce unique business rules in the model.
-//! struct MyVehicleCapacities([f64; 2]);
+//! // Built-in constraints like `CapacityConstraint` cover common rules.
+//! struct MaxRouteStops(usize);
 //!
-//! impl Constraint for MyVehicleCapacities {
+//! impl Constraint for MaxRouteStops {
 //!     fn name(&self) -> String {
-//!         String::from("My Vehicle Capacities")
+//!         String::from("Max Route Stops")
 //!     }
 //!
 //!     // Returns true if the plan is feasible.
-//!     fn is_feasible(&self, plan: &Plan) -> bool {
-//!         todo!()
+//!     fn is_feasible(&self, _model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+//!         solution.vehicles().iter().all(|v| v.route.len() <= self.0)
 //!     }
 //! }
 //!
@@ -53,6 +62,10 @@
 //!         String::from("Simple Operator")
 //!     }
 //!
+//!     fn kind(&self) -> OperatorKind {
+//!         OperatorKind::Destroy
+//!     }
+//!
 //!     // Returns the new plan after executing the operator.
 //!     fn execute(&self, _model: &Model, _solution: &Solution, _random: &mut Random) -> Plan {
 //!         return Plan::new();
@@ -63,7 +76,8 @@
 //!     // Build the model with custom components.
 //!     let model = ModelBuilder::new()
 //!         .objective(ZeroObjective { zero: 0.0 })
-//!         .constraint(MyVehicleCapacities([26.0, 40_000.0]))
+//!         .constraint(CapacityConstraint)
+//!         .constraint(MaxRouteStops(26))
 //!         .build();
 //!
 //!     // Define options for the solver.
@@ -92,14 +106,32 @@
 //! # `Repair`
 //!
 //! The repair operator is responsible for reinserting plan units (planned stops) into the solution.
+//! `RepairOperator` makes the cheapest feasible insertion across all plan units next, while
+//! `RandomRepairOperator` inserts plan units in random order at their cheapest position.
+//! `RegretRepairOperator` inserts the plan units with the most to lose from waiting first.
+//! `BlinkRepairOperator` is the blink insertion of SISR (Slack Induction by String Removals),
+//! which randomly skips some positions while searching for the cheapest one. Repair operators
+//! place the stops of a plan unit one at a time and abandon partial placements that are already
+//! infeasible or costlier than the best insertion found, so long sequences stay cheap to insert.
 //!
 //! # `Destroy`
 //!
 //! The destroy operator is responsible for removing plan units (planned stops) from the solution.
+//! `DestroyOperator` removes random plan units and `NearestDestroyOperator` removes a plan unit
+//! together with its nearest neighbours. `ShawDestroyOperator` removes groups of related plan
+//! units, where relatedness combines distance, time window overlap, and quantity similarity.
+//! `WorstDestroyOperator` removes the plan units whose removal saves the most, and
+//! `StringDestroyOperator` cuts strings of consecutive stops from several nearby routes as in SISR.
+//! `RouteEliminationOperator` empties whole routes and moves their plan units to other vehicles,
+//! which lets the solver minimize the fleet size.
 //!
 //! # `Reset`
 //!
 //! The reset operator helps the solver backtrack by resetting parts of or whole solutions.
+//! After `SolverOptions::reset_after` iterations without a new best solution, the solver applies
+//! a reset operator to the best solution found so far and continues the search from there.
+//! Without a reset operator the search continues from the best solution as is.
+//! `ResetOperator` perturbs the best solution by reinserting some of its plan units at random.
 //!
 //! # `Solver`
 //!
@@ -108,6 +140,26 @@
 //! default, this is a ALNS (Adaptive Large Neighborhood Search) solver that uses multiple strategies to
 //! explore the solution space.
 //!
+//! Every iteration the solver selects one destroy and one repair operator by roulette wheel. The
+//! operators that produce good solutions are rewarded with scores (`OperatorScores`), which are
+//! blended into their selection weights every `SolverOptions::segment_size` iterations.
+//!
+//! The solver stops after `SolverOptions::max_iterations` iterations, or earlier once a time
+//! limit, a number of iterations without improvement, or a target value set on `SolverOptions` is
+//! reached. `SolutionStatistics::stop_reason` reports which criterion ended the solve. A running
+//! solve can also be cancelled from another thread with the `CancellationToken` obtained from
+//! `Solver::cancellation_token`, after which it returns the best solution found so far.
+//!
+//! # `Acceptance`
+//!
+//! An acceptance criterion decides whether the solution produced by an iteration replaces the
+//! current solution, while the solver separately keeps the best solution found so far. Besides
+//! `GreedyAcceptance`, which only accepts improvements, the solver supports simulated annealing,
+//! record-to-record travel, threshold acceptance, and late acceptance hill climbing, which let the
+//! search escape local optima. The default solver uses `SimulatedAnnealingAcceptance`. With
+//! `ObjectiveMode::Lexicographic`, these criteria measure how much worse a solution is on the
+//! first objective level where it differs, rather than on the weighted sum.
+//!
 //! # `Model`
 //!
 //! The `Model` struct represents the vehicle routing problem instance to be solved. It contains all
@@ -121,30 +173,56 @@
 //!
 //! # `Vehicle`
 //!
-//! The `Solver` will assign and unassign routed stops.
+//! The `Solver` will assign and unassign routed stops. A vehicle only visits stops whose
+//! compatibility attributes it has. Vehicles can start and end at depot locations and work within
+//! shifts limited by a start time, an end time, and a maximum route duration.
 //!
 //! # `Stop`
 //!
 //! A stop represents a location that must be visited as part of a plan unit. Stops have specifications
-//! like location coordinates, quantities, and compatibility attributes.
+//! like location coordinates, quantities, compatibility attributes, time windows, and service
+//! durations. Arrival, start, and end times of every routed stop are reported in the solution.
 //!
 //! # `DistanceMatrix`
 //!
-//! A distance matrix provides the distances between each stop in the model.
+//! A distance matrix provides the distances between each stop in the model. A `DurationMatrix`
+//! provides travel durations the same way. Without one, durations are derived from distances and
+//! each vehicle's speed. Objectives choose between the two with `TravelMeasure`.
+//!
+//! # `Profile`
+//!
+//! A profile is a named pair of distance and duration matrices. Vehicles that travel differently,
+//! like bikes and trucks, select their own profile. The matrices set directly on the model belong
+//! to the default profile.
 //!
 //! # `Objective`
 //!
 //! The `Solver` uses implementations of `Objective` to evaluate and optimize solutions. The model is a
-//! weighted sum of all its objectives.
+//! weighted sum of all its objectives, each weighted by the factor given to
+//! `ModelBuilder::weighted_objective`. `Solution::breakdown` reports how much each objective
+//! contributes to a solution's value. With `ObjectiveMode::Lexicographic`, solutions are compared
+//! objective by objective in the order the objectives were added instead, so later objectives only
+//! break ties of earlier ones. The default model uses `UnplannedObjective`, which charges
+//! each unplanned stop its unplanned penalty scaled by its priority, and `TravelDistanceObjective`,
+//! which sums the distance of every route, and `VehicleActivationObjective`, which charges the
+//! fixed cost of every dispatched vehicle. `TravelDurationObjective` optimizes travel time
+//! instead.
 //!
 //! # `Constraint`
 //!
-//! Constraints define the rules for each solution plan.
+//! Constraints define the rules for each solution plan. The default model enforces vehicle
+//! compatibility, time windows, vehicle shifts, and multi-dimensional capacities.
+//!
+//! Objectives and constraints that only depend on each route by itself can say so with
+//! `is_route_local`. When all of them are route-local, as the built-in ones are, repair and
+//! destroy operators evaluate a candidate move with `Solution::evaluate` on the routes it changes
+//! instead of planning the whole solution, and reuse insertions between moves.
 
-mod constraint;
-mod model;
-mod objective;
-mod operator;
-mod random;
-mod solution;
-mod solver;
+pub mod acceptance;
+pub mod constraint;
+pub mod model;
+pub mod objective;
+pub mod operator;
+pub mod random;
+pub mod solution;
+pub mod solver;
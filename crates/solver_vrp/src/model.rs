@@ -1,6 +1,14 @@
+use std::collections::HashMap;
+
 use crate::{
-    constraint::{Constraint, Constraints, VehicleCompatibilityConstraint},
-    objective::{Objective, Objectives, UnplannedObjective},
+    constraint::{
+        CapacityConstraint, Constraint, Constraints, TimeWindowConstraint,
+        VehicleCompatibilityConstraint, VehicleShiftConstraint,
+    },
+    objective::{
+        Objective, ObjectiveMode, Objectives, TravelDistanceObjective, UnplannedObjective,
+        VehicleActivationObjective,
+    },
 };
 
 pub struct Model {
@@ -30,9 +38,119 @@ impl Model {
         &self.data.vehicles
     }
 
+    #[must_use]
+    pub fn plan_units(&self) -> &PlanUnits {
+        &self.data.plan_units
+    }
+
+    #[must_use]
+    pub fn profiles(&self) -> &Profiles {
+        &self.data.profiles
+    }
+
+    /// Travel profile used by the vehicle. Vehicles without a profile use the default profile.
+    #[must_use]
+    pub fn profile(&self, vehicle: usize) -> &Profile {
+        let index = self
+            .data
+            .vehicle_profiles
+            .get(vehicle)
+            .copied()
+            .unwrap_or(0);
+        &self.data.profiles.0[index]
+    }
+
+    /// Distance matrix of the default profile.
     #[must_use]
     pub fn distance_matrix(&self) -> Option<&DistanceMatrix> {
-        self.data.distance_matrix.as_ref()
+        self.data.profiles.default_profile().distance_matrix()
+    }
+
+    /// Duration matrix of the default profile.
+    #[must_use]
+    pub fn duration_matrix(&self) -> Option<&DurationMatrix> {
+        self.data.profiles.default_profile().duration_matrix()
+    }
+
+    /// Distance travelled by a vehicle between two locations, from the distance matrix of the
+    /// vehicle's profile. Profiles without a distance matrix use the haversine distance in meters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a location is outside of the profile's distance matrix. `ModelBuilder::build`
+    /// guarantees this never happens for the locations of the model.
+    #[must_use]
+    pub fn travel_distance(&self, vehicle: usize, from: &Location, to: &Location) -> f64 {
+        let Some(matrix) = self.profile(vehicle).distance_matrix() else {
+            return from.haversine(to);
+        };
+        matrix.get(from.id, to.id).unwrap_or_else(|| {
+            panic!(
+                "locations {} and {} are outside of the distance matrix",
+                from.id, to.id
+            )
+        })
+    }
+
+    /// Time a vehicle takes to travel between two locations, from the duration matrix of the
+    /// vehicle's profile. Profiles without a duration matrix use the travel distance divided by
+    /// the vehicle's speed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a location is outside of the profile's matrices. `ModelBuilder::build`
+    /// guarantees this never happens for the locations of the model.
+    #[must_use]
+    pub fn travel_duration(&self, vehicle: usize, from: &Location, to: &Location) -> f64 {
+        let Some(matrix) = self.profile(vehicle).duration_matrix() else {
+            let speed = self.vehicles().get(vehicle).map_or(1.0, Vehicle::speed);
+            return self.travel_distance(vehicle, from, to) / speed;
+        };
+        matrix.get(from.id, to.id).unwrap_or_else(|| {
+            panic!(
+                "locations {} and {} are outside of the duration matrix",
+                from.id, to.id
+            )
+        })
+    }
+
+    /// Travel cost of a vehicle between two locations in the given measure.
+    #[must_use]
+    pub fn travel(
+        &self,
+        measure: TravelMeasure,
+        vehicle: usize,
+        from: &Location,
+        to: &Location,
+    ) -> f64 {
+        match measure {
+            TravelMeasure::Distance => self.travel_distance(vehicle, from, to),
+            TravelMeasure::Duration => self.travel_duration(vehicle, from, to),
+        }
+    }
+
+    /// Travel cost of a vehicle visiting the stops, given by index, in order. Includes the legs
+    /// from the vehicle's start location and to its end location. An empty route costs nothing.
+    #[must_use]
+    pub fn route_travel(&self, measure: TravelMeasure, vehicle: usize, route: &[usize]) -> f64 {
+        if route.is_empty() {
+            return 0.0;
+        }
+        let model_vehicle = self.vehicles().get(vehicle);
+        let locations: Vec<&Location> = model_vehicle
+            .and_then(Vehicle::start_location)
+            .into_iter()
+            .chain(
+                route
+                    .iter()
+                    .filter_map(|&stop| self.stops().get(stop).map(Stop::location)),
+            )
+            .chain(model_vehicle.and_then(Vehicle::end_location))
+            .collect();
+        locations
+            .windows(2)
+            .map(|leg| self.travel(measure, vehicle, leg[0], leg[1]))
+            .sum()
     }
 
     #[must_use]
@@ -44,13 +162,26 @@ impl Model {
     pub fn constraints(&self) -> &Constraints {
         &self.constraints
     }
+
+    /// Whether every constraint and objective is route-local, so that plans can be evaluated on
+    /// the routes they change with `Solution::evaluate`.
+    #[must_use]
+    pub fn is_route_local(&self) -> bool {
+        self.constraints.iter().all(|c| c.is_route_local())
+            && self.objectives.iter().all(|o| o.is_route_local())
+    }
 }
 
 impl Default for Model {
     fn default() -> Self {
         ModelBuilder::new()
             .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .objective(VehicleActivationObjective)
             .constraint(VehicleCompatibilityConstraint::default())
+            .constraint(TimeWindowConstraint)
+            .constraint(VehicleShiftConstraint)
+            .constraint(CapacityConstraint)
             .build()
     }
 }
@@ -59,48 +190,366 @@ impl Default for Model {
 pub struct ModelData {
     stops: Stops,
     vehicles: Vehicles,
-    distance_matrix: Option<DistanceMatrix>,
+    profiles: Profiles,
+    vehicle_profiles: Vec<usize>,
+    plan_units: PlanUnits,
     graph: DirectedAcyclicGraph,
 }
 
+/// Name of the profile used by vehicles that do not select one.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Travel profiles of the model. The first profile is the default profile.
+pub struct Profiles(Vec<Profile>);
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Profiles(vec![Profile::new(DEFAULT_PROFILE, None, None)])
+    }
+}
+
+impl Profiles {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.0.iter().find(|p| p.name == name)
+    }
+
+    #[must_use]
+    pub fn default_profile(&self) -> &Profile {
+        &self.0[0]
+    }
+
+    /// Adds the profile, replacing any profile with the same name.
+    pub fn push(&mut self, profile: Profile) {
+        match self.0.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.0.push(profile),
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Profile> {
+        self.0.iter()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|p| p.name == name)
+    }
+}
+
+impl<'a> IntoIterator for &'a Profiles {
+    type Item = &'a Profile;
+    type IntoIter = std::slice::Iter<'a, Profile>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A named set of matrices describing how a kind of vehicle travels.
+pub struct Profile {
+    name: String,
+    distance_matrix: Option<DistanceMatrix>,
+    duration_matrix: Option<DurationMatrix>,
+}
+
+impl Profile {
+    #[must_use]
+    pub fn new(
+        name: &str,
+        distance_matrix: Option<DistanceMatrix>,
+        duration_matrix: Option<DurationMatrix>,
+    ) -> Self {
+        Profile {
+            name: name.to_string(),
+            distance_matrix,
+            duration_matrix,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn distance_matrix(&self) -> Option<&DistanceMatrix> {
+        self.distance_matrix.as_ref()
+    }
+
+    #[must_use]
+    pub fn duration_matrix(&self) -> Option<&DurationMatrix> {
+        self.duration_matrix.as_ref()
+    }
+}
+
 #[derive(Default)]
 pub struct Stops(Vec<Stop>);
 
 impl Stops {
+    #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Stop> {
+        self.0.get(index)
+    }
+
     pub fn push(&mut self, stop: Stop) {
         self.0.push(stop);
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Stop> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Stops {
+    type Item = &'a Stop;
+    type IntoIter = std::slice::Iter<'a, Stop>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
 #[derive(Default)]
 pub struct Vehicles(Vec<Vehicle>);
 
 impl Vehicles {
+    #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Vehicle> {
+        self.0.get(index)
+    }
+
     pub fn push(&mut self, vehicle: Vehicle) {
         self.0.push(vehicle);
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Vehicle> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Vehicles {
+    type Item = &'a Vehicle;
+    type IntoIter = std::slice::Iter<'a, Vehicle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A plan unit is the smallest group of stops the solver assigns or unassigns at once.
+///
+/// Stops are stored in the order they must be visited on a route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanUnit {
+    stops: Vec<usize>,
+    precedences: Vec<(usize, usize)>,
+}
+
+impl PlanUnit {
+    #[must_use]
+    pub fn new(stops: Vec<usize>) -> Self {
+        PlanUnit {
+            stops,
+            precedences: Vec::new(),
+        }
+    }
+
+    /// Indices of the stops in this plan unit, in a topological order of their precedences.
+    #[must_use]
+    pub fn stops(&self) -> &[usize] {
+        &self.stops
+    }
+
+    /// Declared precedences as pairs `(before, after)` of indices into `stops`. Stops without a
+    /// precedence between them may be visited in any order.
+    #[must_use]
+    pub fn precedences(&self) -> &[(usize, usize)] {
+        &self.precedences
+    }
+
+    /// True if route positions, one per stop in the order of `stops`, respect every precedence.
+    #[must_use]
+    pub fn is_ordered(&self, positions: &[usize]) -> bool {
+        self.precedences
+            .iter()
+            .all(|&(before, after)| positions[before] < positions[after])
+    }
+
+    /// Every order in which the stops can be visited, as indices into `stops`.
+    #[must_use]
+    pub fn orders(&self) -> Vec<Vec<usize>> {
+        fn extend(unit: &PlanUnit, order: &mut Vec<usize>, all: &mut Vec<Vec<usize>>) {
+            if order.len() == unit.stops.len() {
+                all.push(order.clone());
+                return;
+            }
+            for next in 0..unit.stops.len() {
+                let ready = !order.contains(&next)
+                    && unit
+                        .precedences
+                        .iter()
+                        .all(|&(before, after)| after != next || order.contains(&before));
+                if ready {
+                    order.push(next);
+                    extend(unit, order, all);
+                    order.pop();
+                }
+            }
+        }
+
+        let mut all = Vec::new();
+        extend(self, &mut Vec::with_capacity(self.stops.len()), &mut all);
+        all
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stops.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+}
+
+#[derive(Default)]
+pub struct PlanUnits {
+    units: Vec<PlanUnit>,
+    stop_units: Vec<usize>,
+}
+
+impl PlanUnits {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&PlanUnit> {
+        self.units.get(index)
+    }
+
+    /// Index of the plan unit the stop belongs to.
+    #[must_use]
+    pub fn of_stop(&self, stop: usize) -> Option<usize> {
+        self.stop_units.get(stop).copied()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PlanUnit> {
+        self.units.iter()
+    }
+
+    /// Groups stops connected in the graph into plan units. Only the arcs of the graph become
+    /// precedences, so stops that merely share a sequence partner keep their relative order open.
+    fn from_graph(graph: &DirectedAcyclicGraph) -> Self {
+        let node_count = graph.edges().len();
+        let mut roots: Vec<usize> = (0..node_count).collect();
+        for node in 0..node_count {
+            for arc in graph.outbound(node) {
+                let (a, b) = (
+                    find_root(&mut roots, arc.from),
+                    find_root(&mut roots, arc.to),
+                );
+                roots[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut units: Vec<PlanUnit> = Vec::new();
+        let mut unit_of_root = vec![usize::MAX; node_count];
+        let mut stop_units = vec![0; node_count];
+        let mut unit_indices = vec![0; node_count];
+        for node in graph.topological_order() {
+            let root = find_root(&mut roots, node);
+            if unit_of_root[root] == usize::MAX {
+                unit_of_root[root] = units.len();
+                units.push(PlanUnit::new(Vec::new()));
+            }
+            let unit = &mut units[unit_of_root[root]];
+            stop_units[node] = unit_of_root[root];
+            unit_indices[node] = unit.stops.len();
+            unit.stops.push(node);
+        }
+        for arc in graph.arcs() {
+            let precedence = (unit_indices[arc.from], unit_indices[arc.to]);
+            let unit = &mut units[stop_units[arc.from]];
+            if !unit.precedences.contains(&precedence) {
+                unit.precedences.push(precedence);
+            }
+        }
+
+        PlanUnits { units, stop_units }
+    }
+}
+
+impl<'a> IntoIterator for &'a PlanUnits {
+    type Item = &'a PlanUnit;
+    type IntoIter = std::slice::Iter<'a, PlanUnit>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.units.iter()
+    }
+}
+
+fn find_root(roots: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+    while roots[root] != root {
+        root = roots[root];
+    }
+    roots[node] = root;
+    root
 }
 
 pub struct ModelBuilder {
     data: ModelData,
     objectives: Objectives,
     constraints: Constraints,
+    sequences: Vec<Vec<usize>>,
 }
 
 impl Default for ModelBuilder {
@@ -116,6 +565,7 @@ impl ModelBuilder {
             data: ModelData::default(),
             objectives: Objectives::default(),
             constraints: Constraints::default(),
+            sequences: Vec::new(),
         }
     }
 
@@ -131,14 +581,119 @@ impl ModelBuilder {
         self
     }
 
+    /// Sets the distance matrix of the default profile.
     #[must_use]
     pub fn distance_matrix(mut self, matrix: DistanceMatrix) -> Self {
-        self.data.distance_matrix = Some(matrix);
+        self.data.profiles.0[0].distance_matrix = Some(matrix);
+        self
+    }
+
+    /// Sets the duration matrix of the default profile.
+    #[must_use]
+    pub fn duration_matrix(mut self, matrix: DurationMatrix) -> Self {
+        self.data.profiles.0[0].duration_matrix = Some(matrix);
         self
     }
 
+    /// Adds a travel profile that vehicles can select by name.
     #[must_use]
-    pub fn build(self) -> Model {
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.data.profiles.push(profile);
+        self
+    }
+
+    /// Pairs a pickup with its delivery by stop id. Both stops form one plan unit that is always
+    /// assigned to the same vehicle with the pickup visited first.
+    #[must_use]
+    pub fn pickup_delivery(self, pickup: usize, delivery: usize) -> Self {
+        self.sequence(vec![pickup, delivery])
+    }
+
+    /// Requires stops, by stop id, to be visited by the same vehicle in the given order. Stops
+    /// shared between sequences join their plan units, which only enforce the declared orders.
+    #[must_use]
+    pub fn sequence(mut self, stops: Vec<usize>) -> Self {
+        self.sequences.push(stops);
+        self
+    }
+
+    /// # Panics
+    ///
+    /// Panics if a sequence references an unknown stop id, if sequences form a cycle, if a
+    /// vehicle references an unknown profile, or if a stop or vehicle location is outside of a
+    /// profile's matrix.
+    #[must_use]
+    pub fn build(mut self) -> Model {
+        let locations: Vec<usize> = self
+            .data
+            .stops
+            .iter()
+            .map(|stop| stop.location.id)
+            .chain(self.data.vehicles.iter().flat_map(|vehicle| {
+                [&vehicle.start_location, &vehicle.end_location]
+                    .into_iter()
+                    .flatten()
+                    .map(Location::id)
+            }))
+            .collect();
+        for profile in &self.data.profiles {
+            let covers = |get: &dyn Fn(usize, usize) -> Option<f64>| {
+                locations
+                    .iter()
+                    .all(|&from| locations.iter().all(|&to| get(from, to).is_some()))
+            };
+            assert!(
+                profile
+                    .distance_matrix()
+                    .is_none_or(|matrix| covers(&|from, to| matrix.get(from, to))),
+                "locations are outside of the distance matrix of profile {}",
+                profile.name()
+            );
+            assert!(
+                profile
+                    .duration_matrix()
+                    .is_none_or(|matrix| covers(&|from, to| matrix.get(from, to))),
+                "locations are outside of the duration matrix of profile {}",
+                profile.name()
+            );
+        }
+        self.data.vehicle_profiles = self
+            .data
+            .vehicles
+            .iter()
+            .map(|vehicle| {
+                vehicle.profile().map_or(0, |name| {
+                    self.data
+                        .profiles
+                        .position(name)
+                        .unwrap_or_else(|| panic!("vehicle references unknown profile {name}"))
+                })
+            })
+            .collect();
+        let indices: HashMap<usize, usize> = self
+            .data
+            .stops
+            .iter()
+            .enumerate()
+            .map(|(index, stop)| (stop.id, index))
+            .collect();
+        let mut graph = DirectedAcyclicGraph::with_capacity(self.data.stops.len());
+        for sequence in &self.sequences {
+            for pair in sequence.windows(2) {
+                let index = |id: &usize| {
+                    *indices
+                        .get(id)
+                        .unwrap_or_else(|| panic!("sequence references unknown stop {id}"))
+                };
+                graph.add_arc(index(&pair[0]), index(&pair[1]));
+            }
+        }
+        assert!(
+            graph.topological_order().len() == self.data.stops.len(),
+            "stop sequences must not form a cycle"
+        );
+        self.data.plan_units = PlanUnits::from_graph(&graph);
+        self.data.graph = graph;
         Model {
             data: self.data,
             objectives: self.objectives,
@@ -152,6 +707,20 @@ impl ModelBuilder {
         self
     }
 
+    /// Sets how solutions are compared on their objectives. Defaults to a weighted sum.
+    #[must_use]
+    pub fn objective_mode(mut self, mode: ObjectiveMode) -> Self {
+        self.objectives.set_mode(mode);
+        self
+    }
+
+    /// Adds an objective whose value is multiplied by `factor` in the model's weighted sum.
+    #[must_use]
+    pub fn weighted_objective<O: Objective + 'static>(mut self, objective: O, factor: f64) -> Self {
+        self.objectives.push_weighted(Box::new(objective), factor);
+        self
+    }
+
     #[must_use]
     pub fn constraint<C: Constraint + 'static>(mut self, constraint: C) -> Self {
         self.constraints.push(Box::new(constraint));
@@ -164,17 +733,160 @@ pub struct Stop {
     location: Location,
     quantities: Vec<f64>,
     compatibility_attributes: Option<Vec<CompatibilityAttribute>>,
+    time_windows: Vec<TimeWindow>,
+    duration: f64,
+    unplanned_penalty: Option<f64>,
+    priority: f64,
 }
 
+/// Penalty for leaving a stop unplanned when the stop does not define its own.
+pub const DEFAULT_UNPLANNED_PENALTY: f64 = 1_000_000.0;
+
 impl Stop {
+    #[must_use]
     pub fn new(id: usize, location: Location, quantities: Vec<f64>) -> Self {
         Stop {
             id,
             location,
             quantities,
             compatibility_attributes: None,
+            time_windows: Vec::new(),
+            duration: 0.0,
+            unplanned_penalty: None,
+            priority: 1.0,
+        }
+    }
+
+    #[must_use]
+    pub fn location(&self) -> &Location {
+        &self.location
+    }
+
+    #[must_use]
+    pub fn quantities(&self) -> &[f64] {
+        &self.quantities
+    }
+
+    #[must_use]
+    pub fn compatibility_attributes(&self) -> Option<&[CompatibilityAttribute]> {
+        self.compatibility_attributes.as_deref()
+    }
+
+    /// Hard time windows, sorted by start. Service may start in any of them.
+    #[must_use]
+    pub fn time_windows(&self) -> &[TimeWindow] {
+        &self.time_windows
+    }
+
+    /// Service duration at the stop.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// Cost of leaving the stop unplanned.
+    #[must_use]
+    pub fn unplanned_penalty(&self) -> f64 {
+        self.unplanned_penalty.unwrap_or(DEFAULT_UNPLANNED_PENALTY)
+    }
+
+    /// Factor scaling the unplanned penalty. Higher priority stops are dropped last.
+    #[must_use]
+    pub fn priority(&self) -> f64 {
+        self.priority
+    }
+
+    /// Earliest time service can start when arriving at `arrival`. Returns `None` if every time
+    /// window closes before `arrival`.
+    #[must_use]
+    pub fn earliest_start(&self, arrival: f64) -> Option<f64> {
+        if self.time_windows.is_empty() {
+            return Some(arrival);
+        }
+        self.time_windows
+            .iter()
+            .find(|w| w.end >= arrival)
+            .map(|w| w.start.max(arrival))
+    }
+}
+
+pub struct StopBuilder {
+    stop: Stop,
+}
+
+impl StopBuilder {
+    #[must_use]
+    pub fn new(id: usize, location: Location, quantities: Vec<f64>) -> Self {
+        Self {
+            stop: Stop::new(id, location, quantities),
         }
     }
+
+    /// Adds a hard time window in which service must start.
+    #[must_use]
+    pub fn time_window(mut self, start: f64, end: f64) -> Self {
+        self.stop.time_windows.push(TimeWindow::new(start, end));
+        self
+    }
+
+    #[must_use]
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.stop.duration = duration;
+        self
+    }
+
+    #[must_use]
+    pub fn unplanned_penalty(mut self, penalty: f64) -> Self {
+        self.stop.unplanned_penalty = Some(penalty);
+        self
+    }
+
+    #[must_use]
+    pub fn priority(mut self, priority: f64) -> Self {
+        self.stop.priority = priority;
+        self
+    }
+
+    /// Adds an attribute a vehicle must have to visit the stop.
+    #[must_use]
+    pub fn compatibility_attribute(mut self, attribute: CompatibilityAttribute) -> Self {
+        self.stop
+            .compatibility_attributes
+            .get_or_insert_with(Vec::new)
+            .push(attribute);
+        self
+    }
+
+    #[must_use]
+    pub fn build(mut self) -> Stop {
+        self.stop
+            .time_windows
+            .sort_by(|a, b| a.start.total_cmp(&b.start));
+        self.stop
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeWindow {
+    start: f64,
+    end: f64,
+}
+
+impl TimeWindow {
+    #[must_use]
+    pub fn new(start: f64, end: f64) -> Self {
+        TimeWindow { start, end }
+    }
+
+    #[must_use]
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    #[must_use]
+    pub fn end(&self) -> f64 {
+        self.end
+    }
 }
 
 pub struct Vehicle {
@@ -183,9 +895,16 @@ pub struct Vehicle {
     start_location: Option<Location>,
     end_location: Option<Location>,
     compatibility_attributes: Option<Vec<CompatibilityAttribute>>,
+    shift_start: f64,
+    shift_end: Option<f64>,
+    max_duration: Option<f64>,
+    speed: f64,
+    profile: Option<String>,
+    fixed_cost: f64,
 }
 
 impl Vehicle {
+    #[must_use]
     pub fn new(id: usize, capacity: Vec<f64>) -> Self {
         Vehicle {
             id,
@@ -193,8 +912,170 @@ impl Vehicle {
             start_location: None,
             end_location: None,
             compatibility_attributes: None,
+            shift_start: 0.0,
+            shift_end: None,
+            max_duration: None,
+            speed: 1.0,
+            profile: None,
+            fixed_cost: 0.0,
         }
     }
+
+    #[must_use]
+    pub fn capacity(&self) -> &[f64] {
+        &self.capacity
+    }
+
+    #[must_use]
+    pub fn start_location(&self) -> Option<&Location> {
+        self.start_location.as_ref()
+    }
+
+    #[must_use]
+    pub fn end_location(&self) -> Option<&Location> {
+        self.end_location.as_ref()
+    }
+
+    #[must_use]
+    pub fn compatibility_attributes(&self) -> Option<&[CompatibilityAttribute]> {
+        self.compatibility_attributes.as_deref()
+    }
+
+    /// Earliest time the vehicle leaves its start location. Departure is delayed past it when the
+    /// vehicle would otherwise wait for the first stop's time window to open.
+    #[must_use]
+    pub fn shift_start(&self) -> f64 {
+        self.shift_start
+    }
+
+    /// Latest time the vehicle may finish its route.
+    #[must_use]
+    pub fn shift_end(&self) -> Option<f64> {
+        self.shift_end
+    }
+
+    /// Longest time the vehicle may spend on its route, from departure to route end. Departure
+    /// floats to the first stop's time window, so waiting for it does not count.
+    #[must_use]
+    pub fn max_duration(&self) -> Option<f64> {
+        self.max_duration
+    }
+
+    /// Speed used to derive travel durations from distances.
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Name of the travel profile used by the vehicle.
+    #[must_use]
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Cost charged when the vehicle is dispatched on a non-empty route.
+    #[must_use]
+    pub fn fixed_cost(&self) -> f64 {
+        self.fixed_cost
+    }
+
+    /// Whether the vehicle has every compatibility attribute the stop requires.
+    #[must_use]
+    pub fn is_compatible(&self, stop: &Stop) -> bool {
+        stop.compatibility_attributes()
+            .unwrap_or_default()
+            .iter()
+            .all(|required| {
+                self.compatibility_attributes()
+                    .unwrap_or_default()
+                    .contains(required)
+            })
+    }
+}
+
+pub struct VehicleBuilder {
+    vehicle: Vehicle,
+}
+
+impl VehicleBuilder {
+    #[must_use]
+    pub fn new(id: usize, capacity: Vec<f64>) -> Self {
+        Self {
+            vehicle: Vehicle::new(id, capacity),
+        }
+    }
+
+    #[must_use]
+    pub fn start_location(mut self, location: Location) -> Self {
+        self.vehicle.start_location = Some(location);
+        self
+    }
+
+    #[must_use]
+    pub fn end_location(mut self, location: Location) -> Self {
+        self.vehicle.end_location = Some(location);
+        self
+    }
+
+    /// Working hours of the vehicle. The route departs no earlier than `start`, later if the
+    /// first stop's time window opens later, and must end by `end`.
+    #[must_use]
+    pub fn shift(mut self, start: f64, end: f64) -> Self {
+        self.vehicle.shift_start = start;
+        self.vehicle.shift_end = Some(end);
+        self
+    }
+
+    #[must_use]
+    pub fn shift_start(mut self, start: f64) -> Self {
+        self.vehicle.shift_start = start;
+        self
+    }
+
+    #[must_use]
+    pub fn shift_end(mut self, end: f64) -> Self {
+        self.vehicle.shift_end = Some(end);
+        self
+    }
+
+    #[must_use]
+    pub fn max_duration(mut self, duration: f64) -> Self {
+        self.vehicle.max_duration = Some(duration);
+        self
+    }
+
+    #[must_use]
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.vehicle.speed = speed;
+        self
+    }
+
+    #[must_use]
+    pub fn profile(mut self, name: &str) -> Self {
+        self.vehicle.profile = Some(name.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn fixed_cost(mut self, cost: f64) -> Self {
+        self.vehicle.fixed_cost = cost;
+        self
+    }
+
+    /// Adds an attribute the vehicle offers to stops.
+    #[must_use]
+    pub fn compatibility_attribute(mut self, attribute: CompatibilityAttribute) -> Self {
+        self.vehicle
+            .compatibility_attributes
+            .get_or_insert_with(Vec::new)
+            .push(attribute);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Vehicle {
+        self.vehicle
+    }
 }
 
 pub struct DistanceMatrix {
@@ -202,9 +1083,40 @@ pub struct DistanceMatrix {
 }
 
 impl DistanceMatrix {
+    #[must_use]
     pub fn new(matrix: Vec<Vec<f64>>) -> Self {
         DistanceMatrix { matrix }
     }
+
+    /// Distance between two location indices, if both are in the matrix.
+    #[must_use]
+    pub fn get(&self, from: usize, to: usize) -> Option<f64> {
+        self.matrix.get(from).and_then(|row| row.get(to)).copied()
+    }
+}
+
+pub struct DurationMatrix {
+    matrix: Vec<Vec<f64>>,
+}
+
+impl DurationMatrix {
+    #[must_use]
+    pub fn new(matrix: Vec<Vec<f64>>) -> Self {
+        DurationMatrix { matrix }
+    }
+
+    /// Duration between two location indices, if both are in the matrix.
+    #[must_use]
+    pub fn get(&self, from: usize, to: usize) -> Option<f64> {
+        self.matrix.get(from).and_then(|row| row.get(to)).copied()
+    }
+}
+
+/// Measure used to cost travel between locations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TravelMeasure {
+    Distance,
+    Duration,
 }
 
 pub struct Location {
@@ -214,6 +1126,7 @@ pub struct Location {
 }
 
 impl Location {
+    #[must_use]
     pub fn new(id: usize, latitude: f64, longitude: f64) -> Self {
         Location {
             id,
@@ -221,17 +1134,55 @@ impl Location {
             longitude,
         }
     }
+
+    #[must_use]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    #[must_use]
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    #[must_use]
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// Great-circle distance to another location in meters.
+    #[must_use]
+    pub fn haversine(&self, other: &Location) -> f64 {
+        const EARTH_RADIUS: f64 = 6_371_000.0;
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompatibilityAttribute {
     key: usize,
     value: String,
 }
 
 impl CompatibilityAttribute {
+    #[must_use]
     pub fn new(key: usize, value: String) -> Self {
         CompatibilityAttribute { key, value }
     }
+
+    #[must_use]
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
 }
 
 #[derive(Default)]
@@ -242,10 +1193,6 @@ struct DirectedAcyclicGraph {
 }
 
 impl DirectedAcyclicGraph {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
     pub fn with_capacity(node_count: usize) -> Self {
         Self {
             edges: vec![Vec::new(); node_count],
@@ -272,6 +1219,27 @@ impl DirectedAcyclicGraph {
     pub fn edges(&self) -> &[Vec<usize>] {
         &self.edges
     }
+
+    /// Nodes ordered so that every arc points forward, ties broken by node index.
+    pub fn topological_order(&self) -> Vec<usize> {
+        let mut inbound = vec![0; self.edges.len()];
+        for arc in self.arcs() {
+            inbound[arc.to] += 1;
+        }
+        let mut ready: std::collections::BTreeSet<usize> =
+            (0..self.edges.len()).filter(|&n| inbound[n] == 0).collect();
+        let mut order = Vec::with_capacity(self.edges.len());
+        while let Some(node) = ready.pop_first() {
+            order.push(node);
+            for &to in &self.edges[node] {
+                inbound[to] -= 1;
+                if inbound[to] == 0 {
+                    ready.insert(to);
+                }
+            }
+        }
+        order
+    }
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -281,8 +1249,9 @@ struct Arc {
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
-    use crate::solution::Plan;
+    use crate::solution::{Plan, Solution};
 
     use super::*;
 
@@ -292,7 +1261,7 @@ mod tests {
             String::from("Test Objective")
         }
 
-        fn compute(&self, _plan: &Plan) -> f64 {
+        fn compute(&self, _model: &Model, _solution: &Solution, _plan: &Plan) -> f64 {
             0.0
         }
     }
@@ -303,7 +1272,7 @@ mod tests {
             String::from("Test Constraint")
         }
 
-        fn is_feasible(&self, _plan: &Plan) -> bool {
+        fn is_feasible(&self, _model: &Model, _solution: &Solution, _plan: &Plan) -> bool {
             true
         }
     }
@@ -338,6 +1307,17 @@ mod tests {
         assert_eq!(model.objectives().len(), 2);
     }
 
+    #[test]
+    fn test_model_objective_factors() {
+        let model = ModelBuilder::new()
+            .objective(UnplannedObjective)
+            .weighted_objective(TestObjective, 0.5)
+            .build();
+        assert_eq!(model.objectives().factor(0), Some(1.0));
+        assert_eq!(model.objectives().factor(1), Some(0.5));
+        assert_eq!(model.objectives().weighted_sum(&[2.0, 3.0]), 3.5);
+    }
+
     #[test]
     fn test_model_constraint_count() {
         let model = ModelBuilder::new()
@@ -354,11 +1334,11 @@ mod tests {
             .objective(TestObjective)
             .build();
         assert_eq!(
-            model.objectives().first().map(|o| o.name()),
+            model.objectives().first().map(Objective::name),
             Some(String::from("unplanned"))
         );
         assert_eq!(
-            model.objectives().get(1).map(|o| o.name()),
+            model.objectives().get(1).map(Objective::name),
             Some(String::from("Test Objective"))
         );
     }
@@ -370,15 +1350,192 @@ mod tests {
             .constraint(TestConstraint {})
             .build();
         assert_eq!(
-            model.constraints().first().map(|c| c.name()),
+            model.constraints().first().map(Constraint::name),
             Some(String::from("vehicle_compatibility"))
         );
         assert_eq!(
-            model.constraints().get(1).map(|c| c.name()),
+            model.constraints().get(1).map(Constraint::name),
             Some(String::from("Test Constraint"))
         );
     }
 
+    #[test]
+    fn test_model_plan_units() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(1, Location::new(0, 0.0, 0.0), vec![1.0]))
+            .stop(Stop::new(2, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .build();
+        assert_eq!(model.plan_units().len(), 2);
+        assert_eq!(
+            model.plan_units().get(1).map(PlanUnit::stops),
+            Some(&[1][..])
+        );
+        assert_eq!(model.plan_units().of_stop(1), Some(1));
+    }
+
+    #[test]
+    fn test_model_pickup_delivery() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(10, Location::new(0, 0.0, 0.0), vec![-1.0]))
+            .stop(Stop::new(11, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .stop(Stop::new(12, Location::new(2, 1.0, 1.0), vec![1.0]))
+            .pickup_delivery(11, 10)
+            .build();
+        assert_eq!(model.plan_units().len(), 2);
+        assert_eq!(
+            model.plan_units().get(0).map(PlanUnit::stops),
+            Some(&[1, 0][..])
+        );
+        assert_eq!(model.plan_units().of_stop(0), Some(0));
+        assert_eq!(model.plan_units().of_stop(2), Some(1));
+    }
+
+    #[test]
+    fn test_model_sequences_merge() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![1.0]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .stop(Stop::new(2, Location::new(2, 1.0, 1.0), vec![-1.0]))
+            .stop(Stop::new(3, Location::new(3, 1.0, 0.0), vec![-1.0]))
+            .sequence(vec![0, 2, 3])
+            .pickup_delivery(1, 3)
+            .build();
+        assert_eq!(model.plan_units().len(), 1);
+        let unit = model.plan_units().get(0).unwrap();
+        assert_eq!(unit.stops(), &[0, 1, 2, 3]);
+        assert_eq!(unit.precedences(), &[(0, 2), (2, 3), (1, 3)]);
+        assert_eq!(
+            unit.orders(),
+            vec![vec![0, 1, 2, 3], vec![0, 2, 1, 3], vec![1, 0, 2, 3]]
+        );
+        assert!(unit.is_ordered(&[1, 0, 2, 3]));
+        assert!(!unit.is_ordered(&[0, 3, 1, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn test_model_sequence_cycle() {
+        let _ = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![1.0]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .sequence(vec![0, 1, 0])
+            .build();
+    }
+
+    #[test]
+    fn test_stop_time_windows() {
+        let stop = StopBuilder::new(1, Location::new(1, 0.0, 0.0), vec![])
+            .time_window(50.0, 60.0)
+            .time_window(10.0, 20.0)
+            .duration(5.0)
+            .build();
+        assert_eq!(stop.time_windows()[0], TimeWindow::new(10.0, 20.0));
+        assert_eq!(stop.duration(), 5.0);
+        assert_eq!(stop.earliest_start(0.0), Some(10.0));
+        assert_eq!(stop.earliest_start(15.0), Some(15.0));
+        assert_eq!(stop.earliest_start(21.0), Some(50.0));
+        assert_eq!(stop.earliest_start(61.0), None);
+    }
+
+    #[test]
+    fn test_vehicle_builder() {
+        let vehicle = VehicleBuilder::new(1, vec![10.0])
+            .start_location(Location::new(0, 0.0, 0.0))
+            .end_location(Location::new(1, 1.0, 1.0))
+            .shift(8.0, 17.0)
+            .max_duration(6.0)
+            .build();
+        assert_eq!(vehicle.start_location().map(Location::id), Some(0));
+        assert_eq!(vehicle.end_location().map(Location::id), Some(1));
+        assert_eq!(vehicle.shift_start(), 8.0);
+        assert_eq!(vehicle.shift_end(), Some(17.0));
+        assert_eq!(vehicle.max_duration(), Some(6.0));
+    }
+
+    #[test]
+    fn test_model_travel() {
+        let (a, b, c) = (
+            Location::new(0, 0.0, 0.0),
+            Location::new(1, 0.0, 1.0),
+            Location::new(2, 1.0, 0.0),
+        );
+        let model = ModelBuilder::new()
+            .vehicle(Vehicle::new(0, vec![]))
+            .vehicle(VehicleBuilder::new(1, vec![]).speed(2.0).build())
+            .distance_matrix(DistanceMatrix::new(vec![vec![0.0, 10.0], vec![10.0, 0.0]]))
+            .build();
+        assert_eq!(model.travel_distance(0, &a, &b), 10.0);
+        assert_eq!(model.travel_duration(0, &a, &b), 10.0);
+        assert_eq!(model.travel_duration(1, &a, &b), 5.0);
+        assert_eq!(model.travel(TravelMeasure::Duration, 1, &b, &a), 5.0);
+
+        let model = ModelBuilder::new()
+            .vehicle(VehicleBuilder::new(0, vec![]).speed(2.0).build())
+            .build();
+        assert!((model.travel_distance(0, &a, &c) - 111_195.0).abs() < 1.0);
+        assert!((model.travel_duration(0, &a, &c) - 55_597.5).abs() < 1.0);
+
+        let model = ModelBuilder::new()
+            .vehicle(VehicleBuilder::new(0, vec![]).speed(2.0).build())
+            .distance_matrix(DistanceMatrix::new(vec![vec![0.0, 10.0], vec![10.0, 0.0]]))
+            .duration_matrix(DurationMatrix::new(vec![vec![0.0, 7.0], vec![7.0, 0.0]]))
+            .build();
+        assert_eq!(model.travel(TravelMeasure::Distance, 0, &a, &b), 10.0);
+        assert_eq!(model.travel(TravelMeasure::Duration, 0, &a, &b), 7.0);
+    }
+
+    #[test]
+    fn test_model_profiles() {
+        let (a, b) = (Location::new(0, 0.0, 0.0), Location::new(1, 0.0, 1.0));
+        let matrix = |value: f64| vec![vec![0.0, value], vec![value, 0.0]];
+        let model = ModelBuilder::new()
+            .vehicle(Vehicle::new(0, vec![]))
+            .vehicle(VehicleBuilder::new(1, vec![]).profile("bike").build())
+            .vehicle(VehicleBuilder::new(2, vec![]).profile("truck").build())
+            .distance_matrix(DistanceMatrix::new(matrix(10.0)))
+            .profile(Profile::new(
+                "bike",
+                Some(DistanceMatrix::new(matrix(8.0))),
+                Some(DurationMatrix::new(matrix(40.0))),
+            ))
+            .profile(Profile::new(
+                "truck",
+                Some(DistanceMatrix::new(matrix(12.0))),
+                None,
+            ))
+            .build();
+        assert_eq!(model.profiles().len(), 3);
+        assert_eq!(model.profile(0).name(), DEFAULT_PROFILE);
+        assert_eq!(model.profile(1).name(), "bike");
+        assert_eq!(model.travel_distance(0, &a, &b), 10.0);
+        assert_eq!(model.travel_distance(1, &a, &b), 8.0);
+        assert_eq!(model.travel_duration(1, &a, &b), 40.0);
+        assert_eq!(model.travel_distance(2, &a, &b), 12.0);
+        assert_eq!(model.travel_duration(2, &a, &b), 12.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the distance matrix")]
+    fn test_model_location_outside_matrix() {
+        let _ = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(2, 0.0, 0.0), vec![]))
+            .vehicle(
+                VehicleBuilder::new(0, vec![])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .build(),
+            )
+            .distance_matrix(DistanceMatrix::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]))
+            .build();
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown profile")]
+    fn test_model_unknown_profile() {
+        let _ = ModelBuilder::new()
+            .vehicle(VehicleBuilder::new(0, vec![]).profile("van").build())
+            .build();
+    }
+
     #[test]
     fn test_graph() {
         let mut graph = DirectedAcyclicGraph::with_capacity(3);
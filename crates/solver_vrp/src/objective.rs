@@ -1,33 +1,167 @@
-use crate::solution::Plan;
+use std::cmp::Ordering;
 
+use crate::model::{Model, PlanUnit, TravelMeasure, Vehicle};
+use crate::solution::{Plan, Solution, SolutionVehicle};
+
+/// Objectives of a model with the factor each is weighted by.
 #[derive(Default)]
-pub struct Objectives(Vec<Box<dyn Objective>>);
+pub struct Objectives {
+    entries: Vec<Box<dyn Objective>>,
+    factors: Vec<f64>,
+    mode: ObjectiveMode,
+}
+
+/// How solutions are compared on their objectives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectiveMode {
+    /// Compare the weighted sum of all objectives.
+    #[default]
+    Weighted,
+    /// Compare weighted objective values one at a time, in the order objectives were added. Later
+    /// objectives only break ties of earlier ones.
+    Lexicographic,
+}
 
 pub trait Objective {
     /// Name of the objective.
     fn name(&self) -> String;
-    /// Computes the value of the objective for the given plan.
-    fn compute(&self, plan: &Plan) -> f64;
+    /// Computes the value of the objective for the given plan. The solution is the result of
+    /// applying the plan.
+    fn compute(&self, model: &Model, solution: &Solution, plan: &Plan) -> f64;
+    /// Indicates if the objective is the sum of `route_value` over all routes and
+    /// `unplanned_value` over all unplanned plan units, so that a plan can be evaluated on the
+    /// routes it changes.
+    fn is_route_local(&self) -> bool {
+        false
+    }
+    /// Contribution of a single route, already scheduled. Only used if the objective is
+    /// route-local.
+    fn route_value(&self, _model: &Model, _route: &SolutionVehicle) -> f64 {
+        0.0
+    }
+    /// Contribution of an unplanned plan unit. Only used if the objective is route-local.
+    fn unplanned_value(&self, _model: &Model, _plan_unit: usize) -> f64 {
+        0.0
+    }
+    /// Indicates if `route_value` never decreases when stops are inserted into a route, so that
+    /// the value of a partial insertion bounds the value of the complete one. Travel objectives
+    /// are monotone on travel matrices that satisfy the triangle inequality.
+    fn is_monotone(&self) -> bool {
+        false
+    }
 }
 
 impl Objectives {
+    #[must_use]
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
 
+    #[must_use]
     pub fn get(&self, index: usize) -> Option<&dyn Objective> {
-        self.0.get(index).map(AsRef::as_ref)
+        self.entries.get(index).map(AsRef::as_ref)
     }
 
+    #[must_use]
     pub fn first(&self) -> Option<&dyn Objective> {
-        self.0.first().map(AsRef::as_ref)
+        self.entries.first().map(AsRef::as_ref)
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> ObjectiveMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: ObjectiveMode) {
+        self.mode = mode;
+    }
+
+    /// Factor the objective at `index` is weighted by.
+    #[must_use]
+    pub fn factor(&self, index: usize) -> Option<f64> {
+        self.factors.get(index).copied()
     }
 
     pub fn push(&mut self, objective: Box<dyn Objective>) {
-        self.0.push(objective);
+        self.push_weighted(objective, 1.0);
+    }
+
+    pub fn push_weighted(&mut self, objective: Box<dyn Objective>, factor: f64) {
+        self.entries.push(objective);
+        self.factors.push(factor);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Box<dyn Objective>> {
+        self.entries.iter()
+    }
+
+    /// Unweighted value of every objective, in the order they were added.
+    #[must_use]
+    pub fn compute(&self, model: &Model, solution: &Solution, plan: &Plan) -> Vec<f64> {
+        self.entries
+            .iter()
+            .map(|o| o.compute(model, solution, plan))
+            .collect()
+    }
+
+    /// Weighted sum of objective values computed by `compute`.
+    #[must_use]
+    pub fn weighted_sum(&self, values: &[f64]) -> f64 {
+        self.weighted(values).sum()
+    }
+
+    /// Orders objective values, or changes of them, from better to worse according to the mode.
+    #[must_use]
+    pub fn compare(&self, a: &[f64], b: &[f64]) -> Ordering {
+        match self.mode {
+            ObjectiveMode::Weighted => self.weighted_sum(a).total_cmp(&self.weighted_sum(b)),
+            ObjectiveMode::Lexicographic => self
+                .weighted(a)
+                .zip(self.weighted(b))
+                .map(|(a, b)| a.total_cmp(&b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal),
+        }
+    }
+
+    /// Objective values computed by `compute`, each multiplied by its factor.
+    pub fn weighted(&self, values: &[f64]) -> impl Iterator<Item = f64> {
+        self.factors.iter().zip(values).map(|(f, v)| f * v)
+    }
+}
+
+impl<'a> IntoIterator for &'a Objectives {
+    type Item = &'a Box<dyn Objective>;
+    type IntoIter = std::slice::Iter<'a, Box<dyn Objective>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
     }
 }
 
+/// Contribution of one objective to a solution's value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectiveValue {
+    pub name: String,
+    pub factor: f64,
+    pub value: f64,
+}
+
+impl ObjectiveValue {
+    /// Value multiplied by its factor, as it counts towards the solution's value.
+    #[must_use]
+    pub fn weighted(&self) -> f64 {
+        self.factor * self.value
+    }
+}
+
+/// Penalizes every stop of an unplanned plan unit with its unplanned penalty scaled by its
+/// priority.
 #[derive(Default)]
 pub struct UnplannedObjective;
 
@@ -36,7 +170,210 @@ impl Objective for UnplannedObjective {
         String::from("unplanned")
     }
 
-    fn compute(&self, _plan: &Plan) -> f64 {
-        todo!()
+    fn compute(&self, model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+        solution
+            .unplanned()
+            .iter()
+            .map(|&unit| self.unplanned_value(model, unit))
+            .sum()
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn unplanned_value(&self, model: &Model, plan_unit: usize) -> f64 {
+        model
+            .plan_units()
+            .get(plan_unit)
+            .into_iter()
+            .flat_map(PlanUnit::stops)
+            .filter_map(|&stop| model.stops().get(stop))
+            .map(|stop| stop.unplanned_penalty() * stop.priority())
+            .sum()
+    }
+}
+
+/// Sums the distance travelled on every route.
+#[derive(Default)]
+pub struct TravelDistanceObjective;
+
+impl Objective for TravelDistanceObjective {
+    fn name(&self) -> String {
+        String::from("travel_distance")
+    }
+
+    fn compute(&self, model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+        sum_routes(self, model, solution)
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn route_value(&self, model: &Model, route: &SolutionVehicle) -> f64 {
+        route_travel(model, route, TravelMeasure::Distance)
+    }
+}
+
+/// Sums the time spent travelling on every route.
+#[derive(Default)]
+pub struct TravelDurationObjective;
+
+impl Objective for TravelDurationObjective {
+    fn name(&self) -> String {
+        String::from("travel_duration")
+    }
+
+    fn compute(&self, model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+        sum_routes(self, model, solution)
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn route_value(&self, model: &Model, route: &SolutionVehicle) -> f64 {
+        route_travel(model, route, TravelMeasure::Duration)
+    }
+}
+
+/// Charges the fixed cost of every vehicle with a non-empty route.
+#[derive(Default)]
+pub struct VehicleActivationObjective;
+
+impl Objective for VehicleActivationObjective {
+    fn name(&self) -> String {
+        String::from("vehicle_activation")
+    }
+
+    fn compute(&self, model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+        sum_routes(self, model, solution)
+    }
+
+    fn is_route_local(&self) -> bool {
+        true
+    }
+
+    fn is_monotone(&self) -> bool {
+        true
+    }
+
+    fn route_value(&self, model: &Model, route: &SolutionVehicle) -> f64 {
+        if route.route.is_empty() {
+            return 0.0;
+        }
+        model
+            .vehicles()
+            .get(route.index)
+            .map_or(0.0, Vehicle::fixed_cost)
+    }
+}
+
+fn sum_routes(objective: &dyn Objective, model: &Model, solution: &Solution) -> f64 {
+    solution
+        .vehicles()
+        .iter()
+        .map(|v| objective.route_value(model, v))
+        .sum()
+}
+
+fn route_travel(model: &Model, route: &SolutionVehicle, measure: TravelMeasure) -> f64 {
+    let stops: Vec<usize> = route.route.iter().map(|s| s.stop).collect();
+    model.route_travel(measure, route.index, &stops)
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        DEFAULT_UNPLANNED_PENALTY, DistanceMatrix, DurationMatrix, Location, ModelBuilder, Stop,
+        StopBuilder, Vehicle, VehicleBuilder,
+    };
+
+    #[test]
+    fn test_unplanned_objective() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![]))
+            .stop(
+                StopBuilder::new(1, Location::new(1, 0.0, 0.0), vec![])
+                    .unplanned_penalty(10.0)
+                    .build(),
+            )
+            .stop(
+                StopBuilder::new(2, Location::new(2, 0.0, 0.0), vec![])
+                    .unplanned_penalty(10.0)
+                    .priority(3.0)
+                    .build(),
+            )
+            .vehicle(Vehicle::new(0, vec![]))
+            .objective(UnplannedObjective)
+            .build();
+
+        let solution = Solution::new().plan(&model, &Plan::new()).unwrap();
+        assert_eq!(solution.value(), DEFAULT_UNPLANNED_PENALTY + 40.0);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(2, 0, vec![1]);
+        let solution = solution.plan(&model, &plan).unwrap();
+        assert_eq!(solution.value(), 10.0);
+    }
+
+    #[test]
+    fn test_travel_objectives() {
+        let matrix = vec![
+            vec![0.0, 1.0, 2.0],
+            vec![1.0, 0.0, 4.0],
+            vec![2.0, 4.0, 0.0],
+        ];
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(1, 0.0, 0.0), vec![]))
+            .stop(Stop::new(1, Location::new(2, 0.0, 0.0), vec![]))
+            .vehicle(
+                VehicleBuilder::new(0, vec![])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .end_location(Location::new(0, 0.0, 0.0))
+                    .build(),
+            )
+            .vehicle(Vehicle::new(1, vec![]))
+            .distance_matrix(DistanceMatrix::new(matrix.clone()))
+            .duration_matrix(DurationMatrix::new(
+                matrix
+                    .iter()
+                    .map(|row| row.iter().map(|d| d * 10.0).collect())
+                    .collect(),
+            ))
+            .objective(TravelDistanceObjective)
+            .build();
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![1]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(solution.value(), 7.0);
+        assert_eq!(
+            TravelDurationObjective.compute(&model, &solution, &plan),
+            70.0
+        );
+
+        let mut plan = Plan::new();
+        plan.insert(0, 1, vec![0]);
+        plan.insert(1, 1, vec![1]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(solution.value(), 4.0);
     }
 }
@@ -1,34 +1,57 @@
-use crate::model::Model;
+use crate::model::{Location, Model, PlanUnit, Stop, Vehicle};
 use crate::random::Random;
-use crate::solution::{Plan, Solution};
+use crate::solution::{Plan, Solution, SolutionVehicle};
 
 pub trait Operator {
     /// Name of the operator.
     fn name(&self) -> String;
+    /// Role of the operator in an iteration of the solver.
+    fn kind(&self) -> OperatorKind;
     /// Executes the operator to generate a new solution.
     fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan;
-    /// Chance of applying the operator.
+    /// Initial selection weight of the operator. The solver selects between operators of the same
+    /// kind by roulette wheel over their weights, which start at this value and then adapt to
+    /// the solutions each operator produces.
     fn chance(&self) -> f64 {
         1.0
     }
 }
 
+/// Role of an operator. Every iteration the solver selects one destroy operator followed by one
+/// repair operator. A reset operator is selected instead when the search stagnates, and is applied
+/// to the best solution found so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorKind {
+    Destroy,
+    Repair,
+    Reset,
+}
+
 #[derive(Default)]
 pub struct Operators(Vec<Box<dyn Operator>>);
 
 impl Operators {
+    #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
     pub fn first(&self) -> Option<&dyn Operator> {
         self.0.first().map(AsRef::as_ref)
     }
 
+    #[must_use]
     pub fn get(&self, index: usize) -> Option<&dyn Operator> {
         self.0.get(index).map(AsRef::as_ref)
     }
@@ -42,6 +65,17 @@ impl Operators {
     }
 }
 
+impl<'a> IntoIterator for &'a Operators {
+    type Item = &'a Box<dyn Operator>;
+    type IntoIter = std::slice::Iter<'a, Box<dyn Operator>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Greedily inserts unplanned plan units, always making the cheapest feasible insertion across all
+/// plan units, vehicles and positions next.
 pub struct RepairOperator {
     pub parameters: OperatorParameters,
 }
@@ -49,7 +83,7 @@ pub struct RepairOperator {
 impl Default for RepairOperator {
     fn default() -> Self {
         Self {
-            parameters: OperatorParameters::new(1.0, 1.0),
+            parameters: OperatorParameters::new(Amount::Fraction(1.0), 1.0),
         }
     }
 }
@@ -59,8 +93,225 @@ impl Operator for RepairOperator {
         String::from("repair")
     }
 
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Repair
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        repair_cheapest(model, solution, &self.parameters, random)
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// Inserts the unplanned plan unit with the largest regret next: the gap between the cost of its
+/// best insertion and of its k-th best insertion, each in a different vehicle. Plan units with
+/// fewer than k feasible vehicles come first. `OperatorParameters::value` is k, at least two and
+/// at most the number of vehicles.
+pub struct RegretRepairOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for RegretRepairOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Count(2), 1.0),
+        }
+    }
+}
+
+impl Operator for RegretRepairOperator {
+    fn name(&self) -> String {
+        String::from("repair_regret")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Repair
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        repair_regret(model, solution, &self.parameters, random)
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// Inserts unplanned plan units in random order, each at its cheapest feasible position.
+pub struct RandomRepairOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for RandomRepairOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(1.0), 1.0),
+        }
+    }
+}
+
+impl Operator for RandomRepairOperator {
+    fn name(&self) -> String {
+        String::from("repair_random")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Repair
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        repair_random(model, solution, &self.parameters, random)
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// Blink insertion from Slack Induction by String Removals (SISR). Inserts unplanned plan units
+/// one at a time, ordered randomly, by largest quantity, or by distance from the first vehicle's
+/// start, at the cheapest position found while skipping each candidate position with probability
+/// `blink_rate`.
+pub struct BlinkRepairOperator {
+    pub parameters: OperatorParameters,
+    pub blink_rate: f64,
+}
+
+impl Default for BlinkRepairOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(1.0), 1.0),
+            blink_rate: 0.01,
+        }
+    }
+}
+
+impl Operator for BlinkRepairOperator {
+    fn name(&self) -> String {
+        String::from("repair_blink")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Repair
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        let mut units: Vec<(f64, usize)> = solution
+            .unplanned()
+            .iter()
+            .map(|&unit| (random.f64(), unit))
+            .collect();
+        // Orders weighted 4:4:2:1 as random, largest quantity, farthest, and closest.
+        match random.range_usize(0, 11) {
+            0..4 => {}
+            4..8 => {
+                for unit in &mut units {
+                    unit.0 = -UnitFeatures::new(model, unit.1).quantity;
+                }
+            }
+            order => {
+                let sign = if order < 10 { -1.0 } else { 1.0 };
+                for unit in &mut units {
+                    unit.0 = sign * start_distance(model, unit.1);
+                }
+            }
+        }
+        units.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut plan = Plan::new();
+        let mut current = solution.clone();
+        for (_, unit) in units {
+            if let Some(insertion) = self.blink_insertion(model, &current, unit, random) {
+                commit(model, &mut current, &mut plan, insertion);
+            }
+        }
+        plan
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+impl BlinkRepairOperator {
+    fn blink_insertion(
+        &self,
+        model: &Model,
+        solution: &Solution,
+        plan_unit: usize,
+        random: &mut Random,
+    ) -> Option<Insertion> {
+        (0..model.vehicles().len())
+            .filter_map(|vehicle| {
+                InsertionSearch::new(model, solution, plan_unit, vehicle)?
+                    .run(&mut || random.f64() < self.blink_rate)
+            })
+            .reduce(|best, candidate| {
+                if model
+                    .objectives()
+                    .compare(&candidate.delta, &best.delta)
+                    .is_lt()
+                {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+/// Shortest distance from the first vehicle's start location to a stop of the plan unit, or zero
+/// if the vehicle has no start location.
+fn start_distance(model: &Model, unit: usize) -> f64 {
+    let Some(start) = model.vehicles().get(0).and_then(Vehicle::start_location) else {
+        return 0.0;
+    };
+    model.plan_units().get(unit).map_or(0.0, |u| {
+        u.stops()
+            .iter()
+            .filter_map(|&stop| model.stops().get(stop))
+            .map(|stop| model.travel_distance(0, start, stop.location()))
+            .fold(f64::INFINITY, f64::min)
+    })
+}
+
+/// Restarts the search from the best solution found so far, perturbed by unassigning random plan
+/// units and reinserting them in random order. `OperatorParameters::value` is the fraction, or
+/// count, of assigned plan units to perturb, and zero reverts to the best solution as is.
+pub struct ResetOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for ResetOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(0.1), 1.0),
+        }
+    }
+}
+
+impl Operator for ResetOperator {
+    fn name(&self) -> String {
+        String::from("reset")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Reset
+    }
+
     fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
-        repair_nearest(model, solution, &self.parameters, random)
+        let mut plan = destroy_random(model, solution, &self.parameters, random);
+        if plan.is_empty() {
+            return plan;
+        }
+        let Some(destroyed) = solution.plan(model, &plan) else {
+            return Plan::new();
+        };
+        plan.extend(repair_random(model, &destroyed, &self.parameters, random));
+        plan
     }
 
     fn chance(&self) -> f64 {
@@ -68,6 +319,8 @@ impl Operator for RepairOperator {
     }
 }
 
+/// Unassigns random plan units. `OperatorParameters::value` is the fraction, or count, of assigned
+/// plan units to remove.
 pub struct DestroyOperator {
     pub parameters: OperatorParameters,
 }
@@ -75,7 +328,7 @@ pub struct DestroyOperator {
 impl Default for DestroyOperator {
     fn default() -> Self {
         Self {
-            parameters: OperatorParameters::new(1.0, 1.0),
+            parameters: OperatorParameters::new(Amount::Fraction(0.2), 1.0),
         }
     }
 }
@@ -85,6 +338,10 @@ impl Operator for DestroyOperator {
         String::from("destroy")
     }
 
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
     fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
         destroy_random(model, solution, &self.parameters, random)
     }
@@ -95,49 +352,1473 @@ impl Operator for DestroyOperator {
 }
 
 fn repair_random(
-    _model: &Model,
-    _solution: &Solution,
+    model: &Model,
+    solution: &Solution,
     _params: &OperatorParameters,
-    _random: &mut Random,
+    random: &mut Random,
 ) -> Plan {
-    todo!()
+    let vehicles: Vec<usize> = (0..model.vehicles().len()).collect();
+    let mut units = solution.unplanned().to_vec();
+    let mut plan = Plan::new();
+    let mut current = solution.clone();
+    while !units.is_empty() {
+        let unit = units.swap_remove(random.range_usize(0, units.len()));
+        if let Some(insertion) = cheapest_insertion(model, &current, unit, &vehicles) {
+            commit(model, &mut current, &mut plan, insertion);
+        }
+    }
+    plan
 }
 
-fn repair_nearest(
-    _model: &Model,
-    _solution: &Solution,
+fn repair_cheapest(
+    model: &Model,
+    solution: &Solution,
     _params: &OperatorParameters,
     _random: &mut Random,
 ) -> Plan {
-    todo!()
+    let mut cache = InsertionCache::new(model, solution, solution.unplanned().to_vec());
+    let mut plan = Plan::new();
+    let mut current = solution.clone();
+    loop {
+        let mut best: Option<(usize, &Insertion)> = None;
+        for index in 0..cache.len() {
+            if let Some(insertion) = cache.cheapest(model, index) {
+                if best.is_none_or(|(_, b)| {
+                    model
+                        .objectives()
+                        .compare(&insertion.delta, &b.delta)
+                        .is_lt()
+                }) {
+                    best = Some((index, insertion));
+                }
+            }
+        }
+        let Some((index, _)) = best else {
+            break;
+        };
+        cache.insert(model, &mut current, &mut plan, index);
+    }
+    plan
 }
 
+fn repair_regret(
+    model: &Model,
+    solution: &Solution,
+    params: &OperatorParameters,
+    _random: &mut Random,
+) -> Plan {
+    let k = params.count(model.vehicles().len()).max(2);
+    let objectives = model.objectives();
+    let mut cache = InsertionCache::new(model, solution, solution.unplanned().to_vec());
+    let mut plan = Plan::new();
+    let mut current = solution.clone();
+    loop {
+        // Best candidate as (regret, index of the plan unit, its cheapest insertion).
+        let mut best: Option<(f64, usize, &Insertion)> = None;
+        for index in 0..cache.len() {
+            let mut insertions: Vec<&Insertion> =
+                cache.insertions[index].iter().flatten().collect();
+            if insertions.is_empty() {
+                continue;
+            }
+            insertions.sort_by(|a, b| objectives.compare(&a.delta, &b.delta));
+            let regret = insertions.get(k - 1).map_or(f64::INFINITY, |kth| {
+                objectives.weighted_sum(&kth.delta) - objectives.weighted_sum(&insertions[0].delta)
+            });
+            let insertion = insertions[0];
+            if best.is_none_or(|(r, _, b)| {
+                regret
+                    .total_cmp(&r)
+                    .then_with(|| objectives.compare(&b.delta, &insertion.delta))
+                    .is_gt()
+            }) {
+                best = Some((regret, index, insertion));
+            }
+        }
+        let Some((_, index, _)) = best else {
+            break;
+        };
+        cache.insert(model, &mut current, &mut plan, index);
+    }
+    plan
+}
+
+/// Unassigns randomly chosen plan units.
 fn destroy_random(
     _model: &Model,
-    _solution: &Solution,
-    _params: &OperatorParameters,
-    _random: &mut Random,
+    solution: &Solution,
+    params: &OperatorParameters,
+    random: &mut Random,
 ) -> Plan {
-    todo!()
+    let mut units = assigned_units(solution);
+    let mut plan = Plan::new();
+    for _ in 0..params.count(units.len()) {
+        plan.unassign(units.swap_remove(random.range_usize(0, units.len())));
+    }
+    plan
 }
 
+/// Unassigns a randomly chosen plan unit together with the plan units closest to it.
 fn destroy_nearest(
-    _model: &Model,
-    _solution: &Solution,
-    _params: &OperatorParameters,
-    _random: &mut Random,
+    model: &Model,
+    solution: &Solution,
+    params: &OperatorParameters,
+    random: &mut Random,
+) -> Plan {
+    let units = assigned_units(solution);
+    let count = params.count(units.len());
+    let mut plan = Plan::new();
+    if count == 0 {
+        return plan;
+    }
+    let seed = units[random.range_usize(0, units.len())];
+    let vehicle = solution.vehicle_of(seed).unwrap_or(0);
+    let mut nearest: Vec<(f64, usize)> = units
+        .into_iter()
+        .map(|unit| (unit_distance(model, vehicle, seed, unit), unit))
+        .collect();
+    nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for (_, unit) in nearest.into_iter().take(count) {
+        plan.unassign(unit);
+    }
+    plan
+}
+
+/// Plan units assigned to a vehicle, in index order.
+fn assigned_units(solution: &Solution) -> Vec<usize> {
+    let mut units: Vec<usize> = solution
+        .vehicles()
+        .iter()
+        .flat_map(|v| v.route.iter().map(|s| s.plan_unit))
+        .collect();
+    units.sort_unstable();
+    units.dedup();
+    units
+}
+
+/// Shortest distance between any stop of one plan unit and any stop of another.
+fn unit_distance(model: &Model, vehicle: usize, from: usize, to: usize) -> f64 {
+    let locations = |unit: usize| -> Vec<&Location> {
+        model
+            .plan_units()
+            .get(unit)
+            .map(|u| {
+                u.stops()
+                    .iter()
+                    .filter_map(|&stop| model.stops().get(stop).map(Stop::location))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let targets = locations(to);
+    locations(from)
+        .into_iter()
+        .flat_map(|a| {
+            targets
+                .iter()
+                .map(move |b| model.travel_distance(vehicle, a, b))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Unassigns a random plan unit and the plan units nearest to it, so that a cluster can be
+/// rebuilt. `OperatorParameters::value` is the fraction, or count, of assigned plan units to
+/// remove.
+pub struct NearestDestroyOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for NearestDestroyOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(0.2), 1.0),
+        }
+    }
+}
+
+impl Operator for NearestDestroyOperator {
+    fn name(&self) -> String {
+        String::from("destroy_nearest")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        destroy_nearest(model, solution, &self.parameters, random)
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// Unassigns groups of related plan units (Shaw removal). Starting from a random plan unit, it
+/// repeatedly removes a plan unit related to one already removed. Relatedness combines the distance
+/// between plan units, the overlap of their time windows, and the similarity of their quantities,
+/// each weighted by its factor. `OperatorParameters::value` is the fraction, or count, of assigned
+/// plan units to remove. A higher `OperatorParameters::randomization` exponent removes the most
+/// related plan units more often.
+pub struct ShawDestroyOperator {
+    pub parameters: OperatorParameters,
+    pub distance_weight: f64,
+    pub time_window_weight: f64,
+    pub quantity_weight: f64,
+}
+
+impl Default for ShawDestroyOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(0.2), 1.0).randomization(6.0),
+            distance_weight: 9.0,
+            time_window_weight: 3.0,
+            quantity_weight: 2.0,
+        }
+    }
+}
+
+impl Operator for ShawDestroyOperator {
+    fn name(&self) -> String {
+        String::from("destroy_shaw")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        let mut units = assigned_units(solution);
+        let count = self.parameters.count(units.len());
+        let mut plan = Plan::new();
+        let mut removed = Vec::with_capacity(count);
+        while removed.len() < count {
+            let next = if removed.is_empty() {
+                random.range_usize(0, units.len())
+            } else {
+                let reference = removed[random.range_usize(0, removed.len())];
+                let relatedness = self.relatedness(model, solution, reference, &units);
+                let mut order: Vec<usize> = (0..units.len()).collect();
+                order.sort_by(|&a, &b| relatedness[a].total_cmp(&relatedness[b]));
+                order[skewed_index(random, units.len(), self.parameters.randomization)]
+            };
+            let unit = units.swap_remove(next);
+            plan.unassign(unit);
+            removed.push(unit);
+        }
+        plan
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+impl ShawDestroyOperator {
+    /// Relatedness of every candidate to the reference plan unit. Lower values are more related.
+    fn relatedness(
+        &self,
+        model: &Model,
+        solution: &Solution,
+        reference: usize,
+        candidates: &[usize],
+    ) -> Vec<f64> {
+        let vehicle = solution.vehicle_of(reference).unwrap_or(0);
+        let features = UnitFeatures::new(model, reference);
+        let terms: Vec<(f64, f64, f64)> = candidates
+            .iter()
+            .map(|&unit| {
+                let other = UnitFeatures::new(model, unit);
+                (
+                    unit_distance(model, vehicle, reference, unit),
+                    1.0 - features.overlap(&other),
+                    (features.quantity - other.quantity).abs(),
+                )
+            })
+            .collect();
+        let max = |term: fn(&(f64, f64, f64)) -> f64| {
+            terms.iter().map(term).fold(0.0, f64::max).max(f64::EPSILON)
+        };
+        let (distance, quantity) = (max(|t| t.0), max(|t| t.2));
+        terms
+            .iter()
+            .map(|t| {
+                self.distance_weight * t.0 / distance
+                    + self.time_window_weight * t.1
+                    + self.quantity_weight * t.2 / quantity
+            })
+            .collect()
+    }
+}
+
+/// Aggregated time windows and quantities of a plan unit.
+struct UnitFeatures {
+    window: Option<(f64, f64)>,
+    quantity: f64,
+}
+
+impl UnitFeatures {
+    fn new(model: &Model, unit: usize) -> Self {
+        let stops: Vec<&Stop> = model
+            .plan_units()
+            .get(unit)
+            .map(|u| {
+                u.stops()
+                    .iter()
+                    .filter_map(|&s| model.stops().get(s))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let windows = stops.iter().flat_map(|s| s.time_windows());
+        let window = windows.fold(None, |span: Option<(f64, f64)>, w| {
+            Some(span.map_or((w.start(), w.end()), |(start, end)| {
+                (start.min(w.start()), end.max(w.end()))
+            }))
+        });
+        let quantity = stops
+            .iter()
+            .flat_map(|s| s.quantities())
+            .map(|q| q.abs())
+            .sum();
+        UnitFeatures { window, quantity }
+    }
+
+    /// Share of the shorter time window overlapped by the other. Plan units without time windows
+    /// fully overlap everything.
+    fn overlap(&self, other: &UnitFeatures) -> f64 {
+        let (Some((a_start, a_end)), Some((b_start, b_end))) = (self.window, other.window) else {
+            return 1.0;
+        };
+        let overlap = a_end.min(b_end) - a_start.max(b_start);
+        let shortest = (a_end - a_start).min(b_end - b_start);
+        if overlap < 0.0 {
+            0.0
+        } else if shortest <= 0.0 {
+            1.0
+        } else {
+            overlap / shortest
+        }
+    }
+}
+
+/// Random index below `len` that favours low indices more strongly the higher `exponent` is.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn skewed_index(random: &mut Random, len: usize, exponent: f64) -> usize {
+    ((random.f64().powf(exponent.max(1.0)) * len as f64) as usize).min(len - 1)
+}
+
+/// Unassigns the plan units whose removal saves the most route cost, one at a time. The savings
+/// leave out the unplanned value the removal adds, so that plan units are not kept in place by
+/// their unplanned penalty. `OperatorParameters::value` is the fraction, or count, of assigned plan
+/// units to remove. A higher `OperatorParameters::randomization` exponent removes the costliest
+/// plan units more often.
+pub struct WorstDestroyOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for WorstDestroyOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Fraction(0.2), 1.0).randomization(3.0),
+        }
+    }
+}
+
+impl Operator for WorstDestroyOperator {
+    fn name(&self) -> String {
+        String::from("destroy_worst")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        let mut units = assigned_units(solution);
+        let count = self.parameters.count(units.len());
+        let objectives = model.objectives();
+        let savings = |current: &Solution, unit: usize| {
+            let mut removal = Plan::new();
+            removal.unassign(unit);
+            let delta = current.evaluate(model, &removal)?;
+            let unplanned: Vec<f64> = objectives
+                .iter()
+                .map(|o| o.unplanned_value(model, unit))
+                .collect();
+            Some(objectives.weighted_sum(&unplanned) - objectives.weighted_sum(&delta))
+        };
+        // Savings only change for plan units on the route a removal changes.
+        let mut cached: Vec<Option<f64>> = units.iter().map(|&u| savings(solution, u)).collect();
+        let mut plan = Plan::new();
+        let mut current = solution.clone();
+        for _ in 0..count {
+            let mut removals: Vec<(f64, usize)> = cached
+                .iter()
+                .enumerate()
+                .filter_map(|(index, saving)| saving.map(|s| (s, index)))
+                .collect();
+            if removals.is_empty() {
+                break;
+            }
+            removals.sort_by(|a, b| b.0.total_cmp(&a.0));
+            let pick = skewed_index(random, removals.len(), self.parameters.randomization);
+            let index = removals[pick].1;
+            let unit = units.swap_remove(index);
+            cached.swap_remove(index);
+            let vehicle = current.vehicle_of(unit);
+            let mut removal = Plan::new();
+            removal.unassign(unit);
+            let Some(removed) = current.plan(model, &removal) else {
+                continue;
+            };
+            plan.extend(removal);
+            current = removed;
+            for (&unit, saving) in units.iter().zip(&mut cached) {
+                if !model.is_route_local() || current.vehicle_of(unit) == vehicle {
+                    *saving = savings(&current, unit);
+                }
+            }
+        }
+        plan
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// String removal from Slack Induction by String Removals (SISR). Cuts strings of consecutive
+/// stops from several routes near a random seed plan unit, removing the plan units they belong
+/// to. `OperatorParameters::value` is the fraction, or count, of assigned plan units removed on
+/// average and `max_string_length` caps the number of stops per string.
+pub struct StringDestroyOperator {
+    pub parameters: OperatorParameters,
+    pub max_string_length: usize,
+}
+
+impl Default for StringDestroyOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Count(10), 1.0),
+            max_string_length: 10,
+        }
+    }
+}
+
+impl Operator for StringDestroyOperator {
+    fn name(&self) -> String {
+        String::from("destroy_string")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        let units = assigned_units(solution);
+        let average = self.parameters.count(units.len());
+        let mut plan = Plan::new();
+        if average == 0 {
+            return plan;
+        }
+        let lengths: Vec<usize> = solution
+            .vehicles()
+            .iter()
+            .map(|v| v.route.len())
+            .filter(|&length| length > 0)
+            .collect();
+        let mean_length = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+        let max_length = mean_length.min(self.max_string_length as f64).max(1.0);
+        let max_strings = (4.0 * average as f64 / (1.0 + max_length) - 1.0).max(1.0);
+        let strings = random.range_f64(1.0, max_strings + 1.0) as usize;
+
+        let seed = units[random.range_usize(0, units.len())];
+        let vehicle = solution.vehicle_of(seed).unwrap_or(0);
+        let mut nearest: Vec<(f64, usize)> = units
+            .into_iter()
+            .map(|unit| (unit_distance(model, vehicle, seed, unit), unit))
+            .collect();
+        nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut ruined = Vec::with_capacity(strings);
+        let mut removed = Vec::new();
+        for (_, unit) in nearest {
+            if ruined.len() >= strings {
+                break;
+            }
+            if removed.contains(&unit) {
+                continue;
+            }
+            let Some(route) = solution
+                .vehicle_of(unit)
+                .filter(|v| !ruined.contains(v))
+                .and_then(|v| solution.vehicles().get(v))
+            else {
+                continue;
+            };
+            let Some(position) = route.route.iter().position(|s| s.plan_unit == unit) else {
+                continue;
+            };
+            let size = route.route.len();
+            let length =
+                (random.range_f64(1.0, max_length.min(size as f64) + 1.0) as usize).clamp(1, size);
+            let first = random.range_usize(
+                (position + 1).saturating_sub(length),
+                position.min(size - length) + 1,
+            );
+            for stop in route.route.iter().skip(first).take(length) {
+                if !removed.contains(&stop.plan_unit) {
+                    removed.push(stop.plan_unit);
+                    plan.unassign(stop.plan_unit);
+                }
+            }
+            ruined.push(route.index);
+        }
+        plan
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+/// Empties whole routes and reinserts their plan units into the remaining vehicles. Plan units
+/// that do not fit anywhere else are left unplanned. `OperatorParameters::value` is the number of
+/// routes to empty.
+pub struct RouteEliminationOperator {
+    pub parameters: OperatorParameters,
+}
+
+impl Default for RouteEliminationOperator {
+    fn default() -> Self {
+        Self {
+            parameters: OperatorParameters::new(Amount::Count(1), 1.0),
+        }
+    }
+}
+
+impl Operator for RouteEliminationOperator {
+    fn name(&self) -> String {
+        String::from("route_elimination")
+    }
+
+    fn kind(&self) -> OperatorKind {
+        OperatorKind::Destroy
+    }
+
+    fn execute(&self, model: &Model, solution: &Solution, random: &mut Random) -> Plan {
+        eliminate_routes(model, solution, &self.parameters, random)
+    }
+
+    fn chance(&self) -> f64 {
+        self.parameters.chance_f64
+    }
+}
+
+fn eliminate_routes(
+    model: &Model,
+    solution: &Solution,
+    params: &OperatorParameters,
+    random: &mut Random,
 ) -> Plan {
-    todo!()
+    let mut used: Vec<usize> = solution
+        .vehicles()
+        .iter()
+        .filter(|v| !v.route.is_empty())
+        .map(|v| v.index)
+        .collect();
+    let mut eliminated = Vec::new();
+    for _ in 0..params.count(used.len()) {
+        eliminated.push(used.swap_remove(random.range_usize(0, used.len())));
+    }
+
+    let mut plan = Plan::new();
+    let mut units: Vec<usize> = eliminated
+        .iter()
+        .filter_map(|&v| solution.vehicles().get(v))
+        .flat_map(|v| v.route.iter().map(|s| s.plan_unit))
+        .collect();
+    units.sort_unstable();
+    units.dedup();
+    for &unit in &units {
+        plan.unassign(unit);
+    }
+    let Some(mut current) = solution.plan(model, &plan) else {
+        return Plan::new();
+    };
+    for unit in units {
+        if let Some(insertion) = cheapest_insertion(model, &current, unit, &used) {
+            commit(model, &mut current, &mut plan, insertion);
+        }
+    }
+    plan
+}
+
+/// Insertion of a plan unit into a vehicle's route, with the change of every objective value it
+/// causes as returned by `Solution::evaluate`.
+struct Insertion {
+    plan: Plan,
+    vehicle: usize,
+    delta: Vec<f64>,
+}
+
+/// Cheapest insertion of every plan unit into every vehicle, kept between insertions of a repair
+/// operator. After an insertion only the changed vehicle's insertions are evaluated again, or
+/// every insertion if the model is not route-local.
+struct InsertionCache {
+    units: Vec<usize>,
+    insertions: Vec<Vec<Option<Insertion>>>,
+}
+
+impl InsertionCache {
+    fn new(model: &Model, solution: &Solution, units: Vec<usize>) -> Self {
+        let insertions = units
+            .iter()
+            .map(|&unit| {
+                (0..model.vehicles().len())
+                    .map(|vehicle| cheapest_vehicle_insertion(model, solution, unit, vehicle))
+                    .collect()
+            })
+            .collect();
+        Self { units, insertions }
+    }
+
+    fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Cheapest insertion of the plan unit at `index` into any vehicle.
+    fn cheapest(&self, model: &Model, index: usize) -> Option<&Insertion> {
+        self.insertions[index]
+            .iter()
+            .flatten()
+            .min_by(|a, b| model.objectives().compare(&a.delta, &b.delta))
+    }
+
+    /// Applies the cheapest insertion of the plan unit at `index` to the solution and the plan,
+    /// removes the plan unit from the cache and refreshes the insertions that changed.
+    fn insert(&mut self, model: &Model, solution: &mut Solution, plan: &mut Plan, index: usize) {
+        self.units.swap_remove(index);
+        let Some(insertion) = self
+            .insertions
+            .swap_remove(index)
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| model.objectives().compare(&a.delta, &b.delta))
+        else {
+            return;
+        };
+        let vehicle = insertion.vehicle;
+        if !commit(model, solution, plan, insertion) {
+            return;
+        }
+        for (&unit, insertions) in self.units.iter().zip(&mut self.insertions) {
+            for (v, cached) in insertions.iter_mut().enumerate() {
+                if v == vehicle || !model.is_route_local() {
+                    *cached = cheapest_vehicle_insertion(model, solution, unit, v);
+                }
+            }
+        }
+    }
+}
+
+/// Applies the insertion to the solution and appends it to the plan. Returns false, leaving both
+/// unchanged, if the insertion is infeasible.
+fn commit(model: &Model, solution: &mut Solution, plan: &mut Plan, insertion: Insertion) -> bool {
+    let Some(planned) = solution.plan(model, &insertion.plan) else {
+        return false;
+    };
+    *solution = planned;
+    plan.extend(insertion.plan);
+    true
+}
+
+/// Cheapest feasible insertion of the plan unit into one of the vehicles.
+fn cheapest_insertion(
+    model: &Model,
+    solution: &Solution,
+    plan_unit: usize,
+    vehicles: &[usize],
+) -> Option<Insertion> {
+    vehicles
+        .iter()
+        .filter_map(|&vehicle| cheapest_vehicle_insertion(model, solution, plan_unit, vehicle))
+        .reduce(|best, candidate| {
+            if model
+                .objectives()
+                .compare(&candidate.delta, &best.delta)
+                .is_lt()
+            {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// Cheapest feasible insertion of the plan unit into the vehicle's route.
+fn cheapest_vehicle_insertion(
+    model: &Model,
+    solution: &Solution,
+    plan_unit: usize,
+    vehicle: usize,
+) -> Option<Insertion> {
+    InsertionSearch::new(model, solution, plan_unit, vehicle)?.run(&mut || false)
 }
 
+/// Branch and bound search for the cheapest insertion of a plan unit into a vehicle's route.
+///
+/// Stops are placed one at a time, in every order the plan unit's precedences allow, each after
+/// the position of the previous one. A partial placement is abandoned once it violates a
+/// monotone constraint, or, if every objective is monotone and weighted by a non-negative factor,
+/// once its change of objective values is no better than the cheapest insertion found so far.
+/// Candidate positions are explored from the cheapest partial placement up, so that a good
+/// insertion is found early.
+struct InsertionSearch<'a> {
+    model: &'a Model,
+    solution: &'a Solution,
+    unit: &'a PlanUnit,
+    plan_unit: usize,
+    vehicle: usize,
+    /// Number of stops on the route without the plan unit.
+    length: usize,
+    /// Values of the route before the insertion, per objective, less the unplanned values the
+    /// insertion removes. `None` if partial placements cannot bound the insertion.
+    base: Option<Vec<f64>>,
+    best: Option<Insertion>,
+}
+
+impl<'a> InsertionSearch<'a> {
+    fn new(
+        model: &'a Model,
+        solution: &'a Solution,
+        plan_unit: usize,
+        vehicle: usize,
+    ) -> Option<Self> {
+        let unit = model.plan_units().get(plan_unit)?;
+        let route = solution.vehicles().get(vehicle);
+        let length = route.map_or(0, |v| {
+            v.route.iter().filter(|s| s.plan_unit != plan_unit).count()
+        });
+        let objectives = model.objectives();
+        let assigned = solution.vehicle_of(plan_unit);
+        let bounded = model.is_route_local()
+            && objectives.iter().all(|o| o.is_monotone())
+            && (0..objectives.len()).all(|i| objectives.factor(i).is_some_and(|f| f >= 0.0))
+            && assigned.is_none_or(|v| v == vehicle);
+        let base = bounded.then(|| {
+            let empty = SolutionVehicle::new(vehicle);
+            let route = route.unwrap_or(&empty);
+            objectives
+                .iter()
+                .map(|o| {
+                    let unplanned = if assigned.is_none() {
+                        o.unplanned_value(model, plan_unit)
+                    } else {
+                        0.0
+                    };
+                    o.route_value(model, route) + unplanned
+                })
+                .collect()
+        });
+        Some(Self {
+            model,
+            solution,
+            unit,
+            plan_unit,
+            vehicle,
+            length,
+            base,
+            best: None,
+        })
+    }
+
+    /// Runs the search. `skip` is called for every candidate position and leaves the position out
+    /// when it returns true.
+    fn run(mut self, skip: &mut dyn FnMut() -> bool) -> Option<Insertion> {
+        for order in self.unit.orders() {
+            self.place(&order, &mut Vec::with_capacity(order.len()), skip);
+        }
+        self.best
+    }
+
+    /// Places the next stop of `order` after the stops already placed as `(position, stop)`.
+    fn place(
+        &mut self,
+        order: &[usize],
+        placements: &mut Vec<(usize, usize)>,
+        skip: &mut dyn FnMut() -> bool,
+    ) {
+        let depth = placements.len();
+        let stop = self.unit.stops()[order[depth]];
+        let from = placements.last().map_or(0, |&(position, _)| position + 1);
+        let mut candidates: Vec<(Vec<f64>, usize)> = Vec::new();
+        for position in from..=self.length + depth {
+            if skip() {
+                continue;
+            }
+            placements.push((position, stop));
+            if depth + 1 == order.len() {
+                self.complete(order, placements);
+            } else if let Some(bound) = self.bound(placements) {
+                candidates.push((bound, position));
+            }
+            placements.pop();
+        }
+
+        let objectives = self.model.objectives();
+        candidates.sort_by(|a, b| objectives.compare(&a.0, &b.0));
+        for (bound, position) in candidates {
+            if self.base.is_some()
+                && self
+                    .best
+                    .as_ref()
+                    .is_some_and(|best| !objectives.compare(&bound, &best.delta).is_lt())
+            {
+                break;
+            }
+            placements.push((position, stop));
+            self.place(order, placements, skip);
+            placements.pop();
+        }
+    }
+
+    /// Change of objective values of a partial placement, which no completion of it improves on,
+    /// or `None` if no completion is feasible. Empty if the search is not bounded.
+    fn bound(&self, placements: &[(usize, usize)]) -> Option<Vec<f64>> {
+        let route =
+            self.solution
+                .placed_route(self.model, self.vehicle, self.plan_unit, placements)?;
+        if !self
+            .model
+            .constraints()
+            .iter()
+            .filter(|c| c.is_monotone())
+            .all(|c| c.is_route_feasible(self.model, &route))
+        {
+            return None;
+        }
+        Some(self.base.as_ref().map_or_else(Vec::new, |base| {
+            self.model
+                .objectives()
+                .iter()
+                .zip(base)
+                .map(|(o, base)| o.route_value(self.model, &route) - base)
+                .collect()
+        }))
+    }
+
+    fn complete(&mut self, order: &[usize], placements: &[(usize, usize)]) {
+        let mut positions = vec![0; order.len()];
+        for (&stop, &(position, _)) in order.iter().zip(placements) {
+            positions[stop] = position;
+        }
+        let mut plan = Plan::new();
+        plan.insert(self.plan_unit, self.vehicle, positions);
+        let Some(delta) = self.solution.evaluate(self.model, &plan) else {
+            return;
+        };
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|b| self.model.objectives().compare(&delta, &b.delta).is_lt())
+        {
+            self.best = Some(Insertion {
+                plan,
+                vehicle: self.vehicle,
+                delta,
+            });
+        }
+    }
+}
+
+/// Parameters shared by operators. `chance_f64` is the initial selection weight returned by
+/// `Operator::chance`. `randomization` is the exponent operators that rank plan units use to skew
+/// their random picks towards the top of the ranking; one picks uniformly.
 pub struct OperatorParameters {
-    pub value: f64,
+    pub value: Amount,
     pub chance_f64: f64,
+    pub randomization: f64,
+}
+
+/// Number of items an operator works on, like plan units to remove or routes to empty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Amount {
+    /// Share of the available items between zero and one, rounded up.
+    Fraction(f64),
+    /// Fixed number of items.
+    Count(usize),
 }
 
 impl OperatorParameters {
     #[must_use]
-    pub fn new(value: f64, chance_f64: f64) -> Self {
-        Self { value, chance_f64 }
+    pub fn new(value: Amount, chance_f64: f64) -> Self {
+        Self {
+            value,
+            chance_f64,
+            randomization: 1.0,
+        }
+    }
+
+    #[must_use]
+    pub fn randomization(mut self, exponent: f64) -> Self {
+        self.randomization = exponent;
+        self
+    }
+
+    /// Number of items out of `total` that `value` amounts to. The result never exceeds `total`.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn count(&self, total: usize) -> usize {
+        match self.value {
+            Amount::Fraction(fraction) => {
+                ((fraction.clamp(0.0, 1.0) * total as f64).ceil() as usize).min(total)
+            }
+            Amount::Count(count) => count.min(total),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+    use crate::constraint::{
+        CapacityConstraint, TimeWindowConstraint, VehicleCompatibilityConstraint,
+    };
+    use crate::model::{
+        CompatibilityAttribute, DistanceMatrix, ModelBuilder, StopBuilder, VehicleBuilder,
+    };
+    use crate::objective::{
+        TravelDistanceObjective, UnplannedObjective, VehicleActivationObjective,
+    };
+    use crate::solution::Move;
+
+    #[test]
+    fn test_operator_parameters_count() {
+        let count = |value, total| OperatorParameters::new(value, 1.0).count(total);
+        assert_eq!(count(Amount::Fraction(0.25), 10), 3);
+        assert_eq!(count(Amount::Fraction(0.0), 10), 0);
+        assert_eq!(count(Amount::Fraction(1.0), 10), 10);
+        assert_eq!(count(Amount::Count(1), 10), 1);
+        assert_eq!(count(Amount::Count(4), 10), 4);
+        assert_eq!(count(Amount::Count(40), 10), 10);
+        assert_eq!(DestroyOperator::default().parameters.count(50), 10);
+    }
+
+    /// Every way to place `count` stops in a route that has `length` stops, as strictly increasing
+    /// positions in the resulting route.
+    fn insertion_positions(length: usize, count: usize) -> Vec<Vec<usize>> {
+        fn extend(
+            positions: &mut Vec<usize>,
+            from: usize,
+            size: usize,
+            count: usize,
+            all: &mut Vec<Vec<usize>>,
+        ) {
+            if positions.len() == count {
+                all.push(positions.clone());
+                return;
+            }
+            let remaining = count - positions.len();
+            for position in from..=size - remaining {
+                positions.push(position);
+                extend(positions, position + 1, size, count, all);
+                positions.pop();
+            }
+        }
+
+        let mut all = Vec::new();
+        extend(
+            &mut Vec::with_capacity(count),
+            0,
+            length + count,
+            count,
+            &mut all,
+        );
+        all
+    }
+
+    /// Every way to insert the stops of the plan unit into a route that has `length` stops, in any
+    /// order its precedences allow, as positions per stop in the order of the plan unit's stops.
+    /// Reference for the insertion search.
+    fn unit_positions(unit: &PlanUnit, length: usize) -> Vec<Vec<usize>> {
+        let slots = insertion_positions(length, unit.len());
+        unit.orders()
+            .into_iter()
+            .flat_map(|order| {
+                slots.iter().map(move |slot| {
+                    let mut positions = vec![0; order.len()];
+                    for (&stop, &position) in order.iter().zip(slot) {
+                        positions[stop] = position;
+                    }
+                    positions
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insertion_positions() {
+        assert_eq!(insertion_positions(0, 1), vec![vec![0]]);
+        assert_eq!(insertion_positions(2, 1), vec![vec![0], vec![1], vec![2]]);
+        assert_eq!(
+            insertion_positions(1, 2),
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+        );
+    }
+
+    #[test]
+    fn test_unit_positions() {
+        let unit = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+            .stop(Stop::new(2, Location::new(2, 0.0, 0.0), vec![]))
+            .pickup_delivery(1, 2)
+            .pickup_delivery(0, 2)
+            .build()
+            .plan_units()
+            .get(0)
+            .cloned()
+            .unwrap();
+        assert_eq!(unit_positions(&unit, 0), vec![vec![0, 1, 2], vec![1, 0, 2]]);
+        assert_eq!(unit_positions(&unit, 1).len(), 8);
+    }
+
+    #[test]
+    fn test_insertion_search_sequence() {
+        let coordinates = |id: usize| ((id * 7 % 13) as f64, (id * 5 % 11) as f64);
+        let distance = |a: usize, b: usize| {
+            let ((ax, ay), (bx, by)) = (coordinates(a), coordinates(b));
+            (ax - bx).hypot(ay - by)
+        };
+        let stop = |id| Stop::new(id, Location::new(id, 0.0, 0.0), vec![]);
+        let mut builder = ModelBuilder::new();
+        for id in 0..21 {
+            builder = builder.stop(stop(id));
+        }
+        let model = builder
+            .stop(
+                StopBuilder::new(21, Location::new(21, 0.0, 0.0), vec![])
+                    .time_window(0.0, 60.0)
+                    .build(),
+            )
+            .vehicle(Vehicle::new(0, vec![]))
+            .distance_matrix(DistanceMatrix::new(
+                (0..22)
+                    .map(|a| (0..22).map(|b| distance(a, b)).collect())
+                    .collect(),
+            ))
+            .sequence(vec![16, 17, 18, 19, 20, 21])
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .constraint(TimeWindowConstraint)
+            .build();
+        let mut plan = Plan::new();
+        for stop in 0..16 {
+            plan.insert(model.plan_units().of_stop(stop).unwrap(), 0, vec![stop]);
+        }
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let sequence = model.plan_units().of_stop(16).unwrap();
+        let unit = model.plan_units().get(sequence).unwrap();
+        assert_eq!(unit.len(), 6);
+
+        let objectives = model.objectives();
+        let cheapest = cheapest_vehicle_insertion(&model, &solution, sequence, 0).unwrap();
+        let expected = unit_positions(unit, 16)
+            .into_iter()
+            .filter_map(|positions| {
+                let mut plan = Plan::new();
+                plan.insert(sequence, 0, positions);
+                solution.evaluate(&model, &plan)
+            })
+            .min_by(|a, b| objectives.compare(a, b))
+            .unwrap();
+        assert!(
+            (objectives.weighted_sum(&cheapest.delta) - objectives.weighted_sum(&expected)).abs()
+                < 1e-9
+        );
+        assert!(solution.plan(&model, &cheapest.plan).is_some());
+    }
+
+    fn line_model() -> Model {
+        let stop = |id| Stop::new(id, Location::new(id, 0.0, 0.0), vec![]);
+        let distance = |a: usize, b: usize| a.abs_diff(b) as f64;
+        ModelBuilder::new()
+            .stop(stop(0))
+            .stop(stop(1))
+            .stop(stop(2))
+            .stop(stop(3))
+            .stop(stop(4))
+            .vehicle(Vehicle::new(0, vec![]))
+            .vehicle(Vehicle::new(1, vec![]))
+            .distance_matrix(DistanceMatrix::new(
+                (0..5)
+                    .map(|a| (0..5).map(|b| distance(a, b)).collect())
+                    .collect(),
+            ))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .build()
+    }
+
+    fn line_solution(model: &Model) -> Solution {
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![1]);
+        plan.insert(2, 1, vec![0]);
+        plan.insert(3, 1, vec![1]);
+        plan.insert(4, 1, vec![2]);
+        Solution::new().plan(model, &plan).unwrap()
+    }
+
+    #[test]
+    fn test_destroy_random() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let operator = DestroyOperator {
+            parameters: OperatorParameters::new(Amount::Fraction(0.4), 1.0),
+        };
+        let plan = operator.execute(&model, &solution, &mut Random::seed(3));
+        assert_eq!(plan.len(), 2);
+        let destroyed = solution.plan(&model, &plan).unwrap();
+        assert_eq!(destroyed.unplanned().len(), 2);
+    }
+
+    #[test]
+    fn test_destroy_nearest() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let operator = NearestDestroyOperator {
+            parameters: OperatorParameters::new(Amount::Count(3), 1.0),
+        };
+        for seed in 0..10 {
+            let plan = operator.execute(&model, &solution, &mut Random::seed(seed));
+            let mut removed: Vec<usize> = plan.moves().iter().map(Move::plan_unit).collect();
+            removed.sort_unstable();
+            assert_eq!(removed.len(), 3);
+            assert_eq!(removed[2] - removed[0], 2);
+        }
+    }
+
+    #[test]
+    fn test_repair_cheapest() {
+        let model = line_model();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        let plan = RepairOperator::default().execute(&model, &empty, &mut Random::seed(1));
+        let solution = empty.plan(&model, &plan).unwrap();
+        assert!(solution.unplanned().is_empty());
+        assert_eq!(solution.objective_values()[1], 3.0);
+    }
+
+    #[test]
+    fn test_repair_random() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let mut plan = Plan::new();
+        plan.unassign(1);
+        plan.unassign(3);
+        let destroyed = solution.plan(&model, &plan).unwrap();
+        let plan =
+            RandomRepairOperator::default().execute(&model, &destroyed, &mut Random::seed(5));
+        assert_eq!(plan.len(), 2);
+        let repaired = destroyed.plan(&model, &plan).unwrap();
+        assert!(repaired.unplanned().is_empty());
+    }
+
+    #[test]
+    fn test_repair_regret() {
+        let model = line_model();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        for k in [2, 3] {
+            let operator = RegretRepairOperator {
+                parameters: OperatorParameters::new(Amount::Count(k), 1.0),
+            };
+            let plan = operator.execute(&model, &empty, &mut Random::seed(1));
+            let solution = empty.plan(&model, &plan).unwrap();
+            assert!(solution.unplanned().is_empty());
+            assert_eq!(solution.objective_values()[1], 3.0);
+        }
+    }
+
+    #[test]
+    fn test_repair_regret_order() {
+        // Vehicles start at locations 0 and 1, stops are at locations 2 and 3.
+        let stop = |id| StopBuilder::new(id, Location::new(id + 2, 0.0, 0.0), vec![1.0]);
+        let vehicle =
+            |id| VehicleBuilder::new(id, vec![1.0]).start_location(Location::new(id, 0.0, 0.0));
+        let refrigerated = || CompatibilityAttribute::new(0, String::from("refrigerated"));
+        let model = ModelBuilder::new()
+            .stop(stop(0).compatibility_attribute(refrigerated()).build())
+            .stop(stop(1).build())
+            .vehicle(vehicle(0).compatibility_attribute(refrigerated()).build())
+            .vehicle(vehicle(1).build())
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 5.0, 1.0, 0.0],
+                vec![5.0, 0.0, 5.0, 3.0],
+                vec![1.0, 5.0, 0.0, 5.0],
+                vec![0.0, 3.0, 5.0, 0.0],
+            ]))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .constraint(CapacityConstraint)
+            .constraint(VehicleCompatibilityConstraint::default())
+            .build();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        // The cheapest insertion takes the only vehicle stop 0 fits in.
+        let plan = RepairOperator::default().execute(&model, &empty, &mut Random::seed(1));
+        let greedy = empty.plan(&model, &plan).unwrap();
+        assert_eq!(greedy.unplanned(), &[0]);
+        let plan = RegretRepairOperator::default().execute(&model, &empty, &mut Random::seed(1));
+        let regret = empty.plan(&model, &plan).unwrap();
+        assert!(regret.unplanned().is_empty());
+        assert_eq!(plan.moves()[0].plan_unit(), 0);
+
+        // Three vehicles start at locations 0 to 2, stops are at locations 3 and 4. Stop 0 costs
+        // 0, 10 and 10 to insert, stop 1 costs 1, 2 and 100. Regret-2 inserts stop 0 first and
+        // regret-3 stop 1, so they disagree on who gets the first vehicle.
+        let stop = |id| Stop::new(id, Location::new(id + 3, 0.0, 0.0), vec![1.0]);
+        let vehicle = |id| {
+            VehicleBuilder::new(id, vec![1.0])
+                .start_location(Location::new(id, 0.0, 0.0))
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(stop(0))
+            .stop(stop(1))
+            .vehicle(vehicle(0))
+            .vehicle(vehicle(1))
+            .vehicle(vehicle(2))
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 50.0, 50.0, 0.0, 1.0],
+                vec![50.0, 0.0, 50.0, 10.0, 2.0],
+                vec![50.0, 50.0, 0.0, 10.0, 100.0],
+                vec![0.0, 10.0, 10.0, 0.0, 50.0],
+                vec![1.0, 2.0, 100.0, 50.0, 0.0],
+            ]))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .constraint(CapacityConstraint)
+            .build();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        let repair = |k| {
+            let operator = RegretRepairOperator {
+                parameters: OperatorParameters::new(Amount::Count(k), 1.0),
+            };
+            let plan = operator.execute(&model, &empty, &mut Random::seed(1));
+            empty.plan(&model, &plan).unwrap()
+        };
+        let (two, three) = (repair(2), repair(3));
+        assert_eq!((two.vehicle_of(0), two.vehicle_of(1)), (Some(0), Some(1)));
+        assert_eq!(two.objective_values()[1], 2.0);
+        assert_eq!(
+            (three.vehicle_of(0), three.vehicle_of(1)),
+            (Some(1), Some(0))
+        );
+        assert_eq!(three.objective_values()[1], 11.0);
+    }
+
+    #[test]
+    fn test_repair_blink() {
+        let model = line_model();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        let operator = BlinkRepairOperator {
+            blink_rate: 0.0,
+            ..BlinkRepairOperator::default()
+        };
+        for seed in 0..5 {
+            let plan = operator.execute(&model, &empty, &mut Random::seed(seed));
+            let solution = empty.plan(&model, &plan).unwrap();
+            assert!(solution.unplanned().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_destroy_string() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let operator = StringDestroyOperator {
+            parameters: OperatorParameters::new(Amount::Count(3), 1.0),
+            max_string_length: 2,
+        };
+        for seed in 0..10 {
+            let plan = operator.execute(&model, &solution, &mut Random::seed(seed));
+            assert!(!plan.is_empty());
+            let removed: Vec<usize> = plan.moves().iter().map(Move::plan_unit).collect();
+            for vehicle in solution.vehicles() {
+                let positions: Vec<usize> = vehicle
+                    .route
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| removed.contains(&s.plan_unit))
+                    .map(|(position, _)| position)
+                    .collect();
+                assert!(positions.len() <= 2);
+                assert!(positions.windows(2).all(|w| w[1] == w[0] + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_destroy_shaw() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let operator = ShawDestroyOperator {
+            parameters: OperatorParameters::new(Amount::Count(3), 1.0).randomization(100.0),
+            ..ShawDestroyOperator::default()
+        };
+        for seed in 0..10 {
+            let plan = operator.execute(&model, &solution, &mut Random::seed(seed));
+            let mut removed: Vec<usize> = plan.moves().iter().map(Move::plan_unit).collect();
+            removed.sort_unstable();
+            removed.dedup();
+            assert_eq!(removed.len(), 3);
+            assert_eq!(removed[2] - removed[0], 2);
+        }
+    }
+
+    #[test]
+    fn test_reset() {
+        let model = line_model();
+        let solution = line_solution(&model);
+        let revert = ResetOperator {
+            parameters: OperatorParameters::new(Amount::Count(0), 1.0),
+        };
+        assert!(
+            revert
+                .execute(&model, &solution, &mut Random::seed(1))
+                .is_empty()
+        );
+
+        let operator = ResetOperator {
+            parameters: OperatorParameters::new(Amount::Count(2), 1.0),
+        };
+        let plan = operator.execute(&model, &solution, &mut Random::seed(1));
+        let perturbed = solution.plan(&model, &plan).unwrap();
+        assert!(perturbed.unplanned().is_empty());
+    }
+
+    #[test]
+    fn test_destroy_worst() {
+        let model = line_model();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(4, 0, vec![1]);
+        plan.insert(1, 0, vec![2]);
+        plan.insert(2, 1, vec![0]);
+        plan.insert(3, 1, vec![1]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let operator = WorstDestroyOperator {
+            parameters: OperatorParameters::new(Amount::Count(1), 1.0).randomization(100.0),
+        };
+        let plan = operator.execute(&model, &solution, &mut Random::seed(2));
+        assert_eq!(plan.moves(), &[Move::Unassign { plan_unit: 4 }]);
+    }
+
+    #[test]
+    fn test_destroy_worst_ignores_penalty() {
+        let stop = |id, location, penalty| {
+            StopBuilder::new(id, Location::new(location, 0.0, 0.0), vec![])
+                .unplanned_penalty(penalty)
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(stop(0, 0, 1.0))
+            .stop(stop(1, 1, 1.0))
+            .stop(stop(2, 4, 100.0))
+            .vehicle(Vehicle::new(0, vec![]))
+            .distance_matrix(DistanceMatrix::new(
+                (0..5)
+                    .map(|a: usize| (0..5).map(|b: usize| a.abs_diff(b) as f64).collect())
+                    .collect(),
+            ))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .build();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(2, 0, vec![1]);
+        plan.insert(1, 0, vec![2]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let operator = WorstDestroyOperator {
+            parameters: OperatorParameters::new(Amount::Count(1), 1.0).randomization(100.0),
+        };
+        // Removing plan unit 2 saves 6 of the route's 7 distance but adds a penalty of 100.
+        let plan = operator.execute(&model, &solution, &mut Random::seed(2));
+        assert_eq!(plan.moves(), &[Move::Unassign { plan_unit: 2 }]);
+    }
+
+    #[test]
+    fn test_unit_features_overlap() {
+        let stop = |id, start, end| {
+            StopBuilder::new(id, Location::new(id, 0.0, 0.0), vec![1.0])
+                .time_window(start, end)
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(stop(0, 0.0, 10.0))
+            .stop(stop(1, 5.0, 10.0))
+            .stop(stop(2, 20.0, 30.0))
+            .build();
+        let features = |unit| UnitFeatures::new(&model, unit);
+        assert_eq!(features(0).overlap(&features(1)), 1.0);
+        assert_eq!(features(0).overlap(&features(2)), 0.0);
+        assert_eq!(features(0).quantity, 1.0);
+    }
+
+    #[test]
+    fn test_route_elimination() {
+        let vehicle = |id| {
+            VehicleBuilder::new(id, vec![])
+                .start_location(Location::new(0, 0.0, 0.0))
+                .fixed_cost(100.0)
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(1, 0.0, 0.0), vec![]))
+            .stop(Stop::new(1, Location::new(2, 0.0, 0.0), vec![]))
+            .vehicle(vehicle(0))
+            .vehicle(vehicle(1))
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0],
+                vec![1.0, 1.0, 0.0],
+            ]))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .objective(VehicleActivationObjective)
+            .build();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 1, vec![0]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(solution.value(), 202.0);
+
+        let operator = RouteEliminationOperator::default();
+        let plan = operator.execute(&model, &solution, &mut Random::seed(7));
+        let solution = solution.plan(&model, &plan).unwrap();
+        assert!(solution.unplanned().is_empty());
+        assert_eq!(
+            solution
+                .vehicles()
+                .iter()
+                .filter(|v| !v.route.is_empty())
+                .count(),
+            1
+        );
+        assert_eq!(solution.value(), 102.0);
     }
 }
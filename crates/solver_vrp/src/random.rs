@@ -13,15 +13,16 @@ impl Default for Random {
 }
 
 impl Random {
+    #[must_use]
     pub fn new() -> Self {
         Self::seed(
             SystemTime::now()
                 .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+                .map_or(0, |d| d.as_secs()),
         )
     }
 
+    #[must_use]
     pub fn seed(seed: u64) -> Self {
         Self {
             rng: StdRng::seed_from_u64(seed),
@@ -40,6 +41,10 @@ impl Random {
         self.rng.random_range(low..high)
     }
 
+    pub fn range_usize(&mut self, low: usize, high: usize) -> usize {
+        self.rng.random_range(low..high)
+    }
+
     pub fn range_f64(&mut self, low: f64, high: f64) -> f64 {
         self.rng.random_range(low..high)
     }
@@ -1,10 +1,16 @@
-use crate::model::{Stop, Vehicle};
+use std::cmp::Ordering;
+
+use crate::model::{Model, TravelMeasure};
+use crate::objective::{ObjectiveMode, ObjectiveValue};
 
 #[derive(Clone, Debug)]
 pub struct Solution {
     vehicles: SolutionVehicles,
-    unplanned: SolutionStops,
+    assignments: Vec<Option<usize>>,
+    unplanned: Vec<usize>,
     value: f64,
+    objective_values: Vec<f64>,
+    levels: Option<Vec<f64>>,
     statistics: Option<SolutionStatistics>,
 }
 
@@ -19,8 +25,11 @@ impl Solution {
     pub fn new() -> Self {
         Solution {
             vehicles: SolutionVehicles::new(),
-            unplanned: SolutionStops::new(),
+            assignments: Vec::new(),
+            unplanned: Vec::new(),
             value: 0.0,
+            objective_values: Vec::new(),
+            levels: None,
             statistics: None,
         }
     }
@@ -30,14 +39,219 @@ impl Solution {
         self.value
     }
 
+    /// Weighted objective values compared level by level, or `None` if the solution was valued
+    /// in `ObjectiveMode::Weighted`.
+    #[must_use]
+    pub fn levels(&self) -> Option<&[f64]> {
+        self.levels.as_deref()
+    }
+
+    /// Unweighted value of each objective of the model, in the order they were added.
+    #[must_use]
+    pub fn objective_values(&self) -> &[f64] {
+        &self.objective_values
+    }
+
+    /// Per-objective breakdown of the solution's value.
+    #[must_use]
+    pub fn breakdown(&self, model: &Model) -> Vec<ObjectiveValue> {
+        model
+            .objectives()
+            .iter()
+            .zip(&self.objective_values)
+            .enumerate()
+            .map(|(index, (objective, &value))| ObjectiveValue {
+                name: objective.name(),
+                factor: model.objectives().factor(index).unwrap_or(1.0),
+                value,
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn vehicles(&self) -> &SolutionVehicles {
+        &self.vehicles
+    }
+
+    /// Indices of the plan units that are not assigned to any vehicle.
+    #[must_use]
+    pub fn unplanned(&self) -> &[usize] {
+        &self.unplanned
+    }
+
+    #[must_use]
+    pub fn statistics(&self) -> Option<&SolutionStatistics> {
+        self.statistics.as_ref()
+    }
+
+    pub fn set_statistics(&mut self, statistics: SolutionStatistics) {
+        self.statistics = Some(statistics);
+    }
+
+    /// Vehicle the plan unit is assigned to, if any.
+    #[must_use]
+    pub fn vehicle_of(&self, plan_unit: usize) -> Option<usize> {
+        self.assignments.get(plan_unit).copied().flatten()
+    }
+
+    /// Applies the plan to a copy of the solution. Returns `None` if any move is invalid or the
+    /// resulting solution violates a constraint of the model. Otherwise the new solution is
+    /// valued with the objectives of the model.
+    #[must_use]
+    pub fn plan(&self, model: &Model, plan: &Plan) -> Option<Solution> {
+        let mut solution = self.clone();
+        while solution.vehicles.len() < model.vehicles().len() {
+            let index = solution.vehicles.len();
+            solution.vehicles.push(SolutionVehicle::new(index));
+        }
+
+        for m in plan.moves() {
+            solution.apply(model, m)?;
+        }
+        solution.assignments = vec![None; model.plan_units().len()];
+        for vehicle in &mut solution.vehicles.0 {
+            vehicle.schedule(model);
+            for s in &vehicle.route {
+                if let Some(assignment) = solution.assignments.get_mut(s.plan_unit) {
+                    *assignment = Some(vehicle.index);
+                }
+            }
+        }
+        solution.unplanned = (0..model.plan_units().len())
+            .filter(|&unit| solution.assignments[unit].is_none())
+            .collect();
+
+        if !model
+            .constraints()
+            .iter()
+            .all(|c| c.is_feasible(model, &solution, plan))
+        {
+            return None;
+        }
+        solution.objective_values = model.objectives().compute(model, &solution, plan);
+        solution.value = model.objectives().weighted_sum(&solution.objective_values);
+        solution.levels = match model.objectives().mode() {
+            ObjectiveMode::Weighted => None,
+            ObjectiveMode::Lexicographic => Some(
+                model
+                    .objectives()
+                    .weighted(&solution.objective_values)
+                    .collect(),
+            ),
+        };
+
+        Some(solution)
+    }
+
+    /// Change of every objective value, in the order the objectives were added, that applying the
+    /// plan causes. Returns `None` if any move is invalid or the resulting solution violates a
+    /// constraint of the model. If every objective and constraint of the model is route-local,
+    /// only the routes the plan changes are built and evaluated, which is much cheaper than
+    /// `Solution::plan` on large solutions. Otherwise this falls back to `Solution::plan`.
+    #[must_use]
+    pub fn evaluate(&self, model: &Model, plan: &Plan) -> Option<Vec<f64>> {
+        if !model.is_route_local() {
+            let planned = self.plan(model, plan)?;
+            let before = if self.objective_values.len() == planned.objective_values.len() {
+                self.objective_values.clone()
+            } else {
+                self.plan(model, &Plan::new())?.objective_values
+            };
+            return Some(
+                planned
+                    .objective_values
+                    .iter()
+                    .zip(before)
+                    .map(|(after, before)| after - before)
+                    .collect(),
+            );
+        }
+
+        let mut routes: Vec<SolutionVehicle> = Vec::new();
+        let mut assignments: Vec<(usize, Option<usize>)> = Vec::new();
+        for m in plan.moves() {
+            let plan_unit = m.plan_unit();
+            let assigned = assignments
+                .iter()
+                .rev()
+                .find(|(unit, _)| *unit == plan_unit)
+                .map_or_else(|| self.vehicle_of(plan_unit), |&(_, vehicle)| vehicle);
+            if let Some(vehicle) = assigned {
+                self.changed_route(&mut routes, vehicle).remove(plan_unit);
+            }
+            match m {
+                Move::Insert {
+                    vehicle, positions, ..
+                } => {
+                    if *vehicle >= model.vehicles().len() {
+                        return None;
+                    }
+                    self.changed_route(&mut routes, *vehicle)
+                        .insert(model, plan_unit, positions)?;
+                    assignments.push((plan_unit, Some(*vehicle)));
+                }
+                Move::Unassign { .. } => assignments.push((plan_unit, None)),
+            }
+        }
+
+        let objectives = model.objectives();
+        let mut delta = vec![0.0; objectives.len()];
+        for route in &mut routes {
+            route.schedule(model);
+            if !model
+                .constraints()
+                .iter()
+                .all(|c| c.is_route_feasible(model, route))
+            {
+                return None;
+            }
+            let empty = SolutionVehicle::new(route.index);
+            let before = self.vehicles.get(route.index).unwrap_or(&empty);
+            for (value, objective) in delta.iter_mut().zip(objectives) {
+                *value +=
+                    objective.route_value(model, route) - objective.route_value(model, before);
+            }
+        }
+        let mut units: Vec<usize> = assignments.iter().map(|&(unit, _)| unit).collect();
+        units.sort_unstable();
+        units.dedup();
+        for unit in units {
+            let was_unplanned = self.vehicle_of(unit).is_none();
+            let is_unplanned = assignments
+                .iter()
+                .rev()
+                .find(|(u, _)| *u == unit)
+                .is_some_and(|(_, vehicle)| vehicle.is_none());
+            let sign = match (was_unplanned, is_unplanned) {
+                (false, true) => 1.0,
+                (true, false) => -1.0,
+                _ => continue,
+            };
+            for (value, objective) in delta.iter_mut().zip(objectives) {
+                *value += sign * objective.unplanned_value(model, unit);
+            }
+        }
+        Some(delta)
+    }
+
+    /// Orders solutions from better to worse. Solutions valued in lexicographic mode compare
+    /// weighted objective values level by level, otherwise solutions compare their value.
     #[must_use]
-    pub fn plan(&self, _plan: &Plan) -> Solution {
-        todo!()
+    pub fn compare(&self, other: &Solution) -> Ordering {
+        match (&self.levels, &other.levels) {
+            (Some(levels), Some(other_levels)) => levels
+                .iter()
+                .zip(other_levels)
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal),
+            _ => self.value.total_cmp(&other.value),
+        }
     }
 
     #[must_use]
     pub fn best(self, other: Solution) -> Solution {
-        if self.value < other.value {
+        if self.compare(&other).is_lt() {
             self
         } else {
             other
@@ -45,22 +259,106 @@ impl Solution {
     }
 }
 
+impl Solution {
+    fn apply(&mut self, model: &Model, m: &Move) -> Option<()> {
+        match m {
+            Move::Unassign { plan_unit } => {
+                self.remove(*plan_unit);
+            }
+            Move::Insert {
+                plan_unit,
+                vehicle,
+                positions,
+            } => {
+                if *vehicle >= self.vehicles.len() {
+                    return None;
+                }
+                self.remove(*plan_unit);
+                self.vehicles.0[*vehicle].insert(model, *plan_unit, positions)?;
+            }
+        }
+        Some(())
+    }
+
+    /// Scheduled route of the vehicle with stops of the plan unit placed as `(position, stop)`
+    /// pairs, positions being in the resulting route. The placements may cover only some of the
+    /// plan unit's stops, which lets operators evaluate partial insertions. Returns `None` if a
+    /// position is out of range.
+    pub(crate) fn placed_route(
+        &self,
+        model: &Model,
+        vehicle: usize,
+        plan_unit: usize,
+        placements: &[(usize, usize)],
+    ) -> Option<SolutionVehicle> {
+        let mut route = self
+            .vehicles
+            .get(vehicle)
+            .cloned()
+            .unwrap_or_else(|| SolutionVehicle::new(vehicle));
+        route.remove(plan_unit);
+        let mut placements = placements.to_vec();
+        placements.sort_unstable();
+        for (position, stop) in placements {
+            if position > route.route.len() {
+                return None;
+            }
+            route
+                .route
+                .0
+                .insert(position, SolutionStop::new(stop, plan_unit));
+        }
+        route.schedule(model);
+        Some(route)
+    }
+
+    /// Copy of the vehicle's route among the routes changed by a plan, added on first use.
+    fn changed_route<'a>(
+        &self,
+        routes: &'a mut Vec<SolutionVehicle>,
+        vehicle: usize,
+    ) -> &'a mut SolutionVehicle {
+        let index = routes
+            .iter()
+            .position(|r| r.index == vehicle)
+            .unwrap_or_else(|| {
+                routes.push(
+                    self.vehicles
+                        .get(vehicle)
+                        .cloned()
+                        .unwrap_or_else(|| SolutionVehicle::new(vehicle)),
+                );
+                routes.len() - 1
+            });
+        &mut routes[index]
+    }
+    fn remove(&mut self, plan_unit: usize) {
+        for vehicle in &mut self.vehicles.0 {
+            vehicle.remove(plan_unit);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
-struct SolutionVehicles(Vec<SolutionVehicle>);
+pub struct SolutionVehicles(Vec<SolutionVehicle>);
 
 impl SolutionVehicles {
+    #[must_use]
     pub fn new() -> Self {
         SolutionVehicles(Vec::new())
     }
 
+    #[must_use]
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
     pub fn get(&self, index: usize) -> Option<&SolutionVehicle> {
         self.0.get(index)
     }
@@ -68,41 +366,146 @@ impl SolutionVehicles {
     pub fn push(&mut self, vehicle: SolutionVehicle) {
         self.0.push(vehicle);
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SolutionVehicle> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SolutionVehicles {
+    type Item = &'a SolutionVehicle;
+    type IntoIter = std::slice::Iter<'a, SolutionVehicle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
+impl Default for SolutionVehicles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A vehicle's route. `start` and `end` are the times the vehicle leaves its start location and
+/// finishes its route, including the trip to its end location. The vehicle leaves as late as its
+/// first stop allows without waiting, so that waiting for a late first time window does not count
+/// towards the route's duration. `cost` is the distance travelled.
 #[derive(Clone, Debug)]
-struct SolutionVehicle {
-    pub id: String,
-    pub route: Vec<String>,
+pub struct SolutionVehicle {
+    pub index: usize,
+    pub route: SolutionStops,
     pub cost: f64,
+    pub start: f64,
+    pub end: f64,
 }
 
 impl SolutionVehicle {
-    pub fn new(id: String) -> Self {
+    #[must_use]
+    pub fn new(index: usize) -> Self {
         SolutionVehicle {
-            id,
-            route: Vec::new(),
+            index,
+            route: SolutionStops::new(),
             cost: 0.0,
+            start: 0.0,
+            end: 0.0,
+        }
+    }
+
+    /// Inserts the stops of the plan unit at the positions described by `Move::Insert`. Returns
+    /// `None` without changing the route if the positions are invalid.
+    fn insert(&mut self, model: &Model, plan_unit: usize, positions: &[usize]) -> Option<()> {
+        let unit = model.plan_units().get(plan_unit)?;
+        if positions.len() != unit.len() || !unit.is_ordered(positions) {
+            return None;
+        }
+        let mut placements: Vec<(usize, usize)> = positions
+            .iter()
+            .copied()
+            .zip(unit.stops().iter().copied())
+            .collect();
+        placements.sort_unstable();
+        if placements.windows(2).any(|w| w[0].0 == w[1].0)
+            || placements
+                .last()
+                .is_some_and(|&(p, _)| p >= self.route.len() + unit.len())
+        {
+            return None;
+        }
+        for (position, stop) in placements {
+            self.route
+                .0
+                .insert(position, SolutionStop::new(stop, plan_unit));
+        }
+        Some(())
+    }
+
+    fn remove(&mut self, plan_unit: usize) {
+        self.route.0.retain(|s| s.plan_unit != plan_unit);
+    }
+
+    /// Updates arrival, start and end times along the route. Service starts in the first time
+    /// window still open on arrival, or on arrival if every window is closed. The vehicle departs
+    /// no earlier than the start of its shift, but late enough to arrive at the first stop just as
+    /// service can start there. An empty route starts and ends at the start of the vehicle's
+    /// shift.
+    fn schedule(&mut self, model: &Model) {
+        let vehicle = self.index;
+        let Some(model_vehicle) = model.vehicles().get(vehicle) else {
+            return;
+        };
+        let mut time = model_vehicle.shift_start();
+        let mut location = model_vehicle.start_location();
+        if let Some(stop) = self.route.get(0).and_then(|s| model.stops().get(s.stop)) {
+            let arrival =
+                time + location.map_or(0.0, |l| model.travel_duration(vehicle, l, stop.location()));
+            time += stop
+                .earliest_start(arrival)
+                .map_or(0.0, |start| start - arrival);
         }
+        self.start = time;
+        for s in &mut self.route.0 {
+            let Some(stop) = model.stops().get(s.stop) else {
+                continue;
+            };
+            s.arrival =
+                time + location.map_or(0.0, |l| model.travel_duration(vehicle, l, stop.location()));
+            s.start = stop.earliest_start(s.arrival).unwrap_or(s.arrival);
+            s.end = s.start + stop.duration();
+            time = s.end;
+            location = Some(stop.location());
+        }
+        if !self.route.is_empty() {
+            if let (Some(from), Some(to)) = (location, model_vehicle.end_location()) {
+                time += model.travel_duration(vehicle, from, to);
+            }
+        }
+        self.end = time;
+        let route: Vec<usize> = self.route.iter().map(|s| s.stop).collect();
+        self.cost = model.route_travel(TravelMeasure::Distance, vehicle, &route);
     }
 }
 
 #[derive(Clone, Debug)]
-struct SolutionStops(Vec<SolutionStop>);
+pub struct SolutionStops(Vec<SolutionStop>);
 
 impl SolutionStops {
+    #[must_use]
     pub fn new() -> Self {
         SolutionStops(Vec::new())
     }
 
+    #[must_use]
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
+    #[must_use]
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    #[must_use]
     pub fn get(&self, index: usize) -> Option<&SolutionStop> {
         self.0.get(index)
     }
@@ -110,41 +513,621 @@ impl SolutionStops {
     pub fn push(&mut self, stop: SolutionStop) {
         self.0.push(stop);
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SolutionStop> {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SolutionStops {
+    type Item = &'a SolutionStop;
+    type IntoIter = std::slice::Iter<'a, SolutionStop>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
 }
 
+impl Default for SolutionStops {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A stop visited on a vehicle's route, with the times the vehicle arrives, starts service and
+/// leaves.
 #[derive(Clone, Debug)]
-struct SolutionStop {
-    pub id: String,
-    pub reason: String,
+pub struct SolutionStop {
+    pub stop: usize,
+    pub plan_unit: usize,
+    pub arrival: f64,
+    pub start: f64,
+    pub end: f64,
 }
 
 impl SolutionStop {
-    pub fn new(id: String, reason: String) -> Self {
-        SolutionStop { id, reason }
+    #[must_use]
+    pub fn new(stop: usize, plan_unit: usize) -> Self {
+        SolutionStop {
+            stop,
+            plan_unit,
+            arrival: 0.0,
+            start: 0.0,
+            end: 0.0,
+        }
     }
 }
 
 #[derive(Clone, Debug)]
-struct SolutionStatistics {
+pub struct SolutionStatistics {
     iterations: usize,
     duration: f64,
+    stop_reason: StopReason,
+}
+
+impl SolutionStatistics {
+    #[must_use]
+    pub fn new(iterations: usize, duration: f64, stop_reason: StopReason) -> Self {
+        Self {
+            iterations,
+            duration,
+            stop_reason,
+        }
+    }
+
+    #[must_use]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Wall-clock duration of the solve in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    #[must_use]
+    pub fn stop_reason(&self) -> StopReason {
+        self.stop_reason
+    }
+}
+
+/// Termination criterion that ended a solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The solver ran `SolverOptions::max_iterations` iterations.
+    MaxIterations,
+    /// The solver ran for `SolverOptions::time_limit`.
+    TimeLimit,
+    /// The best solution did not improve for
+    /// `SolverOptions::max_iterations_without_improvement` iterations.
+    Stagnation,
+    /// The best solution reached `SolverOptions::target_value`.
+    TargetValue,
+    /// The solve was cancelled through a `CancellationToken`.
+    Cancelled,
 }
 
-pub struct Plan {}
+/// A single change to a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Move {
+    /// Inserts the stops of a plan unit into a vehicle's route. `positions` holds one distinct
+    /// route index per stop of the plan unit, in the order of the plan unit's stops, and must
+    /// respect the plan unit's precedences. Each index is the position the stop will have in the
+    /// resulting route. A plan unit that is already assigned is moved.
+    Insert {
+        plan_unit: usize,
+        vehicle: usize,
+        positions: Vec<usize>,
+    },
+    /// Removes a plan unit from its route, leaving it unplanned.
+    Unassign { plan_unit: usize },
+}
+
+impl Move {
+    /// The plan unit affected by the move.
+    #[must_use]
+    pub fn plan_unit(&self) -> usize {
+        match self {
+            Move::Insert { plan_unit, .. } | Move::Unassign { plan_unit } => *plan_unit,
+        }
+    }
+}
+
+/// A plan is an ordered set of moves that transform one solution into another. Moves are applied
+/// in order, so positions of later moves refer to routes as changed by earlier moves.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Plan {
+    moves: Vec<Move>,
+}
 
 impl Plan {
     #[must_use]
     pub fn new() -> Self {
-        Plan {}
+        Plan { moves: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
     }
 
     #[must_use]
-    pub fn stop(&self) -> &Stop {
-        todo!()
+    pub fn len(&self) -> usize {
+        self.moves.len()
     }
 
     #[must_use]
-    pub fn vehicle(&self) -> &Vehicle {
-        todo!()
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Adds a move inserting `plan_unit` into `vehicle` at `positions`.
+    pub fn insert(&mut self, plan_unit: usize, vehicle: usize, positions: Vec<usize>) {
+        self.moves.push(Move::Insert {
+            plan_unit,
+            vehicle,
+            positions,
+        });
+    }
+
+    /// Adds a move unassigning `plan_unit`.
+    pub fn unassign(&mut self, plan_unit: usize) {
+        self.moves.push(Move::Unassign { plan_unit });
+    }
+
+    /// Appends the moves of another plan.
+    pub fn extend(&mut self, other: Plan) {
+        self.moves.extend(other.moves);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp, clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+    use crate::constraint::{
+        CapacityConstraint, Constraint, TimeWindowConstraint, VehicleShiftConstraint,
+    };
+    use crate::model::{
+        DistanceMatrix, Location, ModelBuilder, Stop, StopBuilder, Vehicle, VehicleBuilder,
+    };
+    use crate::objective::{Objective, TravelDistanceObjective, UnplannedObjective};
+
+    struct RoutedObjective;
+    impl Objective for RoutedObjective {
+        fn name(&self) -> String {
+            String::from("routed")
+        }
+
+        fn compute(&self, _model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+            solution.unplanned().len() as f64
+        }
+    }
+
+    struct MaxRouteLength(usize);
+    impl Constraint for MaxRouteLength {
+        fn name(&self) -> String {
+            String::from("max_route_length")
+        }
+
+        fn is_feasible(&self, _model: &Model, solution: &Solution, _plan: &Plan) -> bool {
+            solution.vehicles().iter().all(|v| v.route.len() <= self.0)
+        }
+    }
+
+    fn model() -> Model {
+        ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![1.0]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .stop(Stop::new(2, Location::new(2, 1.0, 1.0), vec![1.0]))
+            .vehicle(Vehicle::new(0, vec![2.0]))
+            .vehicle(Vehicle::new(1, vec![2.0]))
+            .objective(RoutedObjective)
+            .constraint(MaxRouteLength(2))
+            .build()
+    }
+
+    fn route(solution: &Solution, vehicle: usize) -> Vec<usize> {
+        solution
+            .vehicles()
+            .get(vehicle)
+            .map(|v| v.route.iter().map(|s| s.stop).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_solution_plan_insert() {
+        let model = model();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![0]);
+        plan.insert(2, 1, vec![0]);
+
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(route(&solution, 0), vec![1, 0]);
+        assert_eq!(route(&solution, 1), vec![2]);
+        assert!(solution.unplanned().is_empty());
+        assert_eq!(solution.vehicle_of(2), Some(1));
+    }
+
+    #[test]
+    fn test_solution_plan_unassign_and_value() {
+        let model = model();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        assert_eq!(empty.vehicles().len(), 2);
+        assert_eq!(empty.unplanned(), &[0, 1, 2]);
+        assert_eq!(empty.value(), 3.0);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 1, vec![0]);
+        let solution = empty.plan(&model, &plan).unwrap();
+        assert_eq!(solution.value(), 1.0);
+
+        let mut plan = Plan::new();
+        plan.unassign(0);
+        plan.insert(1, 0, vec![0]);
+        let solution = solution.plan(&model, &plan).unwrap();
+        assert_eq!(route(&solution, 0), vec![1]);
+        assert_eq!(route(&solution, 1), Vec::<usize>::new());
+        assert_eq!(solution.unplanned(), &[0, 2]);
+        assert_eq!(solution.value(), 2.0);
+    }
+
+    #[test]
+    fn test_solution_breakdown() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+            .vehicle(Vehicle::new(0, vec![]))
+            .weighted_objective(RoutedObjective, 10.0)
+            .objective(RoutedObjective)
+            .build();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(solution.objective_values(), &[1.0, 1.0]);
+        assert_eq!(solution.value(), 11.0);
+
+        let breakdown = solution.breakdown(&model);
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].name, "routed");
+        assert_eq!(breakdown[0].weighted(), 10.0);
+        assert_eq!(breakdown[1].weighted(), 1.0);
+    }
+
+    #[test]
+    fn test_solution_best_lexicographic() {
+        struct RouteLength;
+        impl Objective for RouteLength {
+            fn name(&self) -> String {
+                String::from("route_length")
+            }
+
+            fn compute(&self, _model: &Model, solution: &Solution, _plan: &Plan) -> f64 {
+                solution
+                    .vehicles()
+                    .iter()
+                    .map(|v| v.route.len())
+                    .sum::<usize>() as f64
+            }
+        }
+
+        let builder = || {
+            ModelBuilder::new()
+                .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![]))
+                .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+                .vehicle(Vehicle::new(0, vec![]))
+                .objective(RoutedObjective)
+                .weighted_objective(RouteLength, 10.0)
+        };
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+
+        let model = builder().build();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        let routed = empty.plan(&model, &plan).unwrap();
+        assert_eq!((empty.value(), routed.value()), (2.0, 11.0));
+        assert!(empty.clone().best(routed).unplanned().len() == 2);
+
+        let model = builder()
+            .objective_mode(ObjectiveMode::Lexicographic)
+            .build();
+        let empty = Solution::new().plan(&model, &Plan::new()).unwrap();
+        let routed = empty.plan(&model, &plan).unwrap();
+        assert_eq!(routed.compare(&empty), Ordering::Less);
+        assert!(empty.best(routed).unplanned().len() == 1);
+    }
+
+    #[test]
+    fn test_solution_plan_pickup_delivery() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(0, Location::new(0, 0.0, 0.0), vec![-1.0]))
+            .stop(Stop::new(1, Location::new(1, 0.0, 1.0), vec![1.0]))
+            .stop(Stop::new(2, Location::new(2, 1.0, 1.0), vec![1.0]))
+            .vehicle(Vehicle::new(0, vec![2.0]))
+            .pickup_delivery(1, 0)
+            .build();
+
+        let mut plan = Plan::new();
+        plan.insert(1, 0, vec![0]);
+        plan.insert(0, 0, vec![0, 2]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(route(&solution, 0), vec![1, 2, 0]);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![1, 0]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+    }
+
+    #[test]
+    fn test_solution_plan_shared_delivery() {
+        let stop = |id| Stop::new(id, Location::new(id, 0.0, 0.0), vec![]);
+        let model = ModelBuilder::new()
+            .stop(stop(0))
+            .stop(stop(1))
+            .stop(stop(2))
+            .vehicle(Vehicle::new(0, vec![]))
+            .pickup_delivery(1, 2)
+            .pickup_delivery(0, 2)
+            .build();
+        assert_eq!(model.plan_units().len(), 1);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![1, 0, 2]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        assert_eq!(route(&solution, 0), vec![1, 0, 2]);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0, 2, 1]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0, 0, 2]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+    }
+
+    #[test]
+    fn test_solution_plan_schedule() {
+        let model = ModelBuilder::new()
+            .stop(
+                StopBuilder::new(0, Location::new(0, 0.0, 0.0), vec![])
+                    .time_window(10.0, 20.0)
+                    .duration(5.0)
+                    .build(),
+            )
+            .stop(
+                StopBuilder::new(1, Location::new(1, 0.0, 1.0), vec![])
+                    .time_window(0.0, 16.0)
+                    .duration(2.0)
+                    .build(),
+            )
+            .vehicle(Vehicle::new(0, vec![]))
+            .distance_matrix(DistanceMatrix::new(vec![vec![0.0, 3.0], vec![3.0, 0.0]]))
+            .constraint(TimeWindowConstraint)
+            .build();
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![0]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let stops: Vec<(f64, f64, f64)> = solution
+            .vehicles()
+            .get(0)
+            .unwrap()
+            .route
+            .iter()
+            .map(|s| (s.arrival, s.start, s.end))
+            .collect();
+        assert_eq!(stops, vec![(0.0, 0.0, 2.0), (5.0, 10.0, 15.0)]);
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![1]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+    }
+
+    #[test]
+    fn test_solution_plan_late_first_window() {
+        let model = ModelBuilder::new()
+            .stop(
+                StopBuilder::new(1, Location::new(1, 0.0, 0.0), vec![])
+                    .time_window(10.0, 11.0)
+                    .duration(1.0)
+                    .build(),
+            )
+            .vehicle(
+                VehicleBuilder::new(0, vec![])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .end_location(Location::new(0, 0.0, 0.0))
+                    .max_duration(4.0)
+                    .build(),
+            )
+            .distance_matrix(DistanceMatrix::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]))
+            .constraint(TimeWindowConstraint)
+            .constraint(VehicleShiftConstraint)
+            .build();
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let vehicle = solution.vehicles().get(0).unwrap();
+        assert_eq!((vehicle.start, vehicle.end), (9.0, 12.0));
+        let stop = vehicle.route.get(0).unwrap();
+        assert_eq!((stop.arrival, stop.start, stop.end), (10.0, 10.0, 11.0));
+    }
+
+    #[test]
+    fn test_solution_plan_shift() {
+        let stop = |id| {
+            StopBuilder::new(id, Location::new(id, 0.0, 0.0), vec![])
+                .duration(1.0)
+                .build()
+        };
+        let model = ModelBuilder::new()
+            .stop(stop(1))
+            .stop(stop(2))
+            .vehicle(
+                VehicleBuilder::new(0, vec![])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .end_location(Location::new(0, 0.0, 0.0))
+                    .shift(10.0, 16.0)
+                    .build(),
+            )
+            .vehicle(
+                VehicleBuilder::new(1, vec![])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .max_duration(4.0)
+                    .build(),
+            )
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 1.0, 3.0],
+                vec![1.0, 0.0, 1.0],
+                vec![2.0, 1.0, 0.0],
+            ]))
+            .constraint(VehicleShiftConstraint)
+            .build();
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![1]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let vehicle = solution.vehicles().get(0).unwrap();
+        assert_eq!((vehicle.start, vehicle.end), (10.0, 16.0));
+        assert_eq!(vehicle.cost, 4.0);
+        let vehicle = solution.vehicles().get(1).unwrap();
+        assert_eq!((vehicle.start, vehicle.end), (0.0, 0.0));
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![0]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+
+        let mut plan = Plan::new();
+        plan.insert(1, 1, vec![0]);
+        assert!(Solution::new().plan(&model, &plan).is_some());
+        plan.insert(0, 1, vec![1]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+    }
+
+    #[test]
+    fn test_solution_evaluate() {
+        let fallback = model();
+        let stop = |id| Stop::new(id, Location::new(id, 0.0, 0.0), vec![1.0]);
+        let model = ModelBuilder::new()
+            .stop(stop(1))
+            .stop(stop(2))
+            .stop(stop(3))
+            .vehicle(
+                VehicleBuilder::new(0, vec![2.0])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .build(),
+            )
+            .vehicle(Vehicle::new(1, vec![2.0]))
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![1.0, 0.0, 1.0, 2.0],
+                vec![2.0, 1.0, 0.0, 1.0],
+                vec![3.0, 2.0, 1.0, 0.0],
+            ]))
+            .constraint(CapacityConstraint)
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .build();
+        assert!(model.is_route_local());
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 1, vec![0]);
+        let solution = Solution::new().plan(&model, &plan).unwrap();
+        let planned = |plan: &Plan| {
+            let planned = solution.plan(&model, plan).unwrap();
+            let expected: Vec<f64> = planned
+                .objective_values
+                .iter()
+                .zip(&solution.objective_values)
+                .map(|(after, before)| after - before)
+                .collect();
+            assert_eq!(solution.evaluate(&model, plan), Some(expected));
+        };
+
+        let mut plan = Plan::new();
+        plan.insert(2, 0, vec![1]);
+        planned(&plan);
+        let mut plan = Plan::new();
+        plan.insert(1, 0, vec![0]);
+        plan.unassign(0);
+        planned(&plan);
+        let mut plan = Plan::new();
+        plan.unassign(1);
+        plan.insert(2, 1, vec![0]);
+        planned(&plan);
+
+        let mut plan = Plan::new();
+        plan.insert(1, 0, vec![1]);
+        plan.insert(2, 0, vec![2]);
+        assert!(solution.evaluate(&model, &plan).is_none());
+
+        assert!(!fallback.is_route_local());
+        let solution = Solution::new().plan(&fallback, &Plan::new()).unwrap();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        assert_eq!(solution.evaluate(&fallback, &plan), Some(vec![-1.0]));
+    }
+
+    #[test]
+    fn test_solution_plan_rejects_infeasible() {
+        let model = model();
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        plan.insert(1, 0, vec![1]);
+        plan.insert(2, 0, vec![2]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![1]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+
+        let mut plan = Plan::new();
+        plan.insert(0, 2, vec![0]);
+        assert!(Solution::new().plan(&model, &plan).is_none());
+    }
+
+    #[test]
+    fn test_plan_moves() {
+        let mut plan = Plan::new();
+        assert!(plan.is_empty());
+
+        plan.insert(0, 1, vec![0, 2]);
+        plan.unassign(3);
+        plan.insert(4, 1, vec![1]);
+
+        assert_eq!(plan.len(), 3);
+        assert_eq!(
+            plan.moves()[0],
+            Move::Insert {
+                plan_unit: 0,
+                vehicle: 1,
+                positions: vec![0, 2]
+            }
+        );
+        assert_eq!(plan.moves()[1].plan_unit(), 3);
+    }
+
+    #[test]
+    fn test_plan_extend() {
+        let mut plan = Plan::new();
+        plan.unassign(0);
+        let mut other = Plan::new();
+        other.insert(0, 2, vec![0]);
+        plan.extend(other);
+
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan.moves()[1].plan_unit(), 0);
     }
 }
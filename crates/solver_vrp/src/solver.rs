@@ -1,15 +1,27 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::acceptance::{Acceptance, GreedyAcceptance, SimulatedAnnealingAcceptance};
 use crate::model::Model;
-use crate::operator::{DestroyOperator, Operator, Operators, RepairOperator};
+use crate::operator::{
+    DestroyOperator, Operator, OperatorKind, Operators, RepairOperator, ResetOperator,
+};
 use crate::random::Random;
-use crate::solution::Solution;
+use crate::solution::{Plan, Solution, SolutionStatistics, StopReason};
 
 pub struct Solver {
     model: Model,
     operators: Operators,
     options: SolverOptions,
     solution: Option<Solution>,
+    best: Option<Solution>,
+    acceptance: Box<dyn Acceptance>,
     random: Random,
+    weights: OperatorWeights,
+    cancellation: CancellationToken,
     pub iteration_count: usize,
+    iterations_without_improvement: usize,
 }
 
 impl Solver {
@@ -20,8 +32,13 @@ impl Solver {
             operators: Operators::new(),
             options: SolverOptions::default(),
             solution: None,
+            best: None,
+            acceptance: Box::new(GreedyAcceptance),
             random: Random::new(),
+            weights: OperatorWeights::default(),
+            cancellation: CancellationToken::new(),
             iteration_count: 0,
+            iterations_without_improvement: 0,
         }
     }
 
@@ -40,44 +57,279 @@ impl Solver {
         &self.options
     }
 
+    /// Current solution of the search, which starts as the initial solution.
     #[must_use]
     pub fn solution(&self) -> Option<&Solution> {
         self.solution.as_ref()
     }
 
+    /// Best solution found so far.
+    #[must_use]
+    pub fn best(&self) -> Option<&Solution> {
+        self.best.as_ref()
+    }
+
+    #[must_use]
+    pub fn acceptance(&self) -> &dyn Acceptance {
+        self.acceptance.as_ref()
+    }
+
+    /// Current selection weight of each operator, in the order the operators were added.
+    #[must_use]
+    pub fn operator_weights(&self) -> &[f64] {
+        &self.weights.weights
+    }
+
+    /// Token that cancels the solve from another thread. After cancellation, `solve` completes
+    /// the current iteration and returns the best solution found so far.
+    #[must_use]
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
     #[must_use]
     pub fn solve(mut self) -> Option<Solution> {
-        while self.iteration_count < self.options.max_iterations {
+        let initial = self.solution.take().unwrap_or_default();
+        self.solution = initial
+            .plan(&self.model, &Plan::new())
+            .or_else(|| Solution::new().plan(&self.model, &Plan::new()));
+        self.best.clone_from(&self.solution);
+        self.weights = OperatorWeights::new(&self.operators);
+        let start = Instant::now();
+        let stop_reason = loop {
+            if let Some(reason) = self.stop_reason(start) {
+                break reason;
+            }
             self.execute_operators();
             self.increment_iteration();
+        };
+        let mut best = self.best?;
+        best.set_statistics(SolutionStatistics::new(
+            self.iteration_count,
+            start.elapsed().as_secs_f64(),
+            stop_reason,
+        ));
+        Some(best)
+    }
+
+    /// First termination criterion of the options that is met, if any.
+    fn stop_reason(&self, start: Instant) -> Option<StopReason> {
+        let options = &self.options;
+        if self.cancellation.is_cancelled() {
+            Some(StopReason::Cancelled)
+        } else if options
+            .target_value
+            .zip(self.best.as_ref())
+            .is_some_and(|(target, best)| best.value() <= target)
+        {
+            Some(StopReason::TargetValue)
+        } else if self.iteration_count >= options.max_iterations {
+            Some(StopReason::MaxIterations)
+        } else if options
+            .max_iterations_without_improvement
+            .is_some_and(|limit| self.iterations_without_improvement >= limit)
+        {
+            Some(StopReason::Stagnation)
+        } else if options
+            .time_limit
+            .is_some_and(|limit| start.elapsed() >= limit)
+        {
+            Some(StopReason::TimeLimit)
+        } else {
+            None
         }
-        self.solution
     }
 
     fn increment_iteration(&mut self) {
         self.iteration_count += 1;
+        if self.iteration_count % self.options.segment_size.max(1) == 0 {
+            self.weights.update(self.options.reaction_factor);
+        }
     }
 
+    /// Runs one destroy and one repair operator, each selected by roulette wheel over the
+    /// operator weights, and rewards both by how the resulting solution compares. The acceptance
+    /// criterion decides whether the result replaces the current solution.
     fn execute_operators(&mut self) {
-        let mut solution = self.solution.take().unwrap_or_default();
-        for op in self.operators.iter() {
-            if !self.random.chance((op.chance(), 1.0)) {
+        if self.is_stagnant() {
+            self.reset();
+        }
+        let (Some(solution), Some(best)) = (self.solution.take(), self.best.take()) else {
+            return;
+        };
+        let selected: Vec<usize> = [OperatorKind::Destroy, OperatorKind::Repair]
+            .into_iter()
+            .filter_map(|kind| self.weights.select(&self.operators, kind, &mut self.random))
+            .collect();
+        let mut candidate = solution.clone();
+        for &index in &selected {
+            let Some(op) = self.operators.get(index) else {
                 continue;
+            };
+            let plan = op.execute(&self.model, &candidate, &mut self.random);
+            if let Some(planned) = candidate.plan(&self.model, &plan) {
+                candidate = planned;
             }
-            solution = solution
-                .plan(&op.execute(&self.model, &solution, &mut self.random))
-                .best(solution);
         }
-        self.solution = Some(solution);
+        let accepted = self
+            .acceptance
+            .accept(&candidate, &solution, &best, &mut self.random);
+        let scores = &self.options.scores;
+        let score = if candidate.compare(&best).is_lt() {
+            scores.best
+        } else if candidate.compare(&solution).is_lt() {
+            scores.improved
+        } else if accepted {
+            scores.accepted
+        } else {
+            0.0
+        };
+        for index in selected {
+            self.weights.reward(index, score);
+        }
+        if candidate.compare(&best).is_lt() {
+            self.iterations_without_improvement = 0;
+        } else {
+            self.iterations_without_improvement += 1;
+        }
+        self.best = Some(candidate.clone().best(best));
+        self.solution = Some(if accepted { candidate } else { solution });
     }
 }
 
+impl Solver {
+    /// True once every `SolverOptions::reset_after` iterations without a new best solution.
+    fn is_stagnant(&self) -> bool {
+        self.options.reset_after.is_some_and(|after| {
+            self.iterations_without_improvement > 0
+                && self.iterations_without_improvement % after.max(1) == 0
+        })
+    }
+
+    /// Replaces the current solution by the best solution, perturbed by a reset operator selected
+    /// by roulette wheel. Without reset operators the search continues from the best solution as
+    /// is.
+    fn reset(&mut self) {
+        let Some(best) = self.best.as_ref() else {
+            return;
+        };
+        let Some(op) = self
+            .weights
+            .select(&self.operators, OperatorKind::Reset, &mut self.random)
+            .and_then(|index| self.operators.get(index))
+        else {
+            self.solution = Some(best.clone());
+            return;
+        };
+        let plan = op.execute(&self.model, best, &mut self.random);
+        self.solution = best.plan(&self.model, &plan).or_else(|| Some(best.clone()));
+    }
+}
+
+/// Shared flag to cooperatively cancel a running solve. Clones share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Adaptive selection weights of the operators. Scores collected during a segment of iterations
+/// are blended into the weights at the end of the segment.
+#[derive(Default)]
+struct OperatorWeights {
+    weights: Vec<f64>,
+    scores: Vec<f64>,
+    uses: Vec<usize>,
+}
+
+impl OperatorWeights {
+    fn new(operators: &Operators) -> Self {
+        Self {
+            weights: operators.iter().map(|op| op.chance().max(0.0)).collect(),
+            scores: vec![0.0; operators.len()],
+            uses: vec![0; operators.len()],
+        }
+    }
+
+    /// Roulette wheel selection among the operators of the kind.
+    fn select(
+        &self,
+        operators: &Operators,
+        kind: OperatorKind,
+        random: &mut Random,
+    ) -> Option<usize> {
+        let candidates: Vec<usize> = operators
+            .iter()
+            .enumerate()
+            .filter(|(_, op)| op.kind() == kind)
+            .map(|(index, _)| index)
+            .collect();
+        let total: f64 = candidates.iter().map(|&i| self.weights[i]).sum();
+        if total <= 0.0 {
+            return candidates
+                .get(random.range_usize(0, candidates.len().max(1)))
+                .copied();
+        }
+        let mut spin = random.f64() * total;
+        for &index in &candidates {
+            spin -= self.weights[index];
+            if spin < 0.0 {
+                return Some(index);
+            }
+        }
+        candidates.last().copied()
+    }
+
+    fn reward(&mut self, index: usize, score: f64) {
+        self.scores[index] += score;
+        self.uses[index] += 1;
+    }
+
+    /// Blends the average score of every operator used during the segment into its weight and
+    /// starts a new segment.
+    #[allow(clippy::cast_precision_loss)]
+    fn update(&mut self, reaction_factor: f64) {
+        for ((weight, score), uses) in self
+            .weights
+            .iter_mut()
+            .zip(&mut self.scores)
+            .zip(&mut self.uses)
+        {
+            if *uses > 0 {
+                *weight = (*weight * (1.0 - reaction_factor)
+                    + reaction_factor * *score / *uses as f64)
+                    .max(MIN_OPERATOR_WEIGHT);
+            }
+            *score = 0.0;
+            *uses = 0;
+        }
+    }
+}
+
+/// Lower bound on adaptive operator weights, so that no operator is ever excluded for good.
+const MIN_OPERATOR_WEIGHT: f64 = 0.01;
+
 impl Default for Solver {
     fn default() -> Self {
         SolverBuilder::new()
             .model(Model::default())
             .operator(DestroyOperator::default())
             .operator(RepairOperator::default())
+            .operator(ResetOperator::default())
+            .acceptance(SimulatedAnnealingAcceptance::default())
             .options(SolverOptions::default())
             .build()
     }
@@ -102,6 +354,15 @@ impl SolverBuilder {
         self
     }
 
+    /// Criterion deciding whether a new solution replaces the current solution. Defaults to
+    /// `GreedyAcceptance` for `SolverBuilder::new`, and to `SimulatedAnnealingAcceptance` for
+    /// `SolverBuilder::default`, which starts from `Solver::default`.
+    #[must_use]
+    pub fn acceptance<A: Acceptance + 'static>(mut self, acceptance: A) -> Self {
+        self.solver.acceptance = Box::new(acceptance);
+        self
+    }
+
     #[must_use]
     pub fn options(mut self, options: SolverOptions) -> Self {
         self.solver.options = options;
@@ -114,6 +375,14 @@ impl SolverBuilder {
         self
     }
 
+    /// Shares the token with the solver, so that it can be cancelled with a token created before
+    /// the solver is built.
+    #[must_use]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.solver.cancellation = token;
+        self
+    }
+
     #[must_use]
     pub fn solution(mut self, solution: Solution) -> Self {
         self.solver.solution = Some(solution);
@@ -126,14 +395,77 @@ impl SolverBuilder {
     }
 }
 
+/// Options of the solver. The solver stops as soon as any of its termination criteria is met:
+/// `max_iterations`, and optionally `time_limit`, `max_iterations_without_improvement`, and
+/// `target_value`.
 pub struct SolverOptions {
     max_iterations: usize,
+    time_limit: Option<Duration>,
+    max_iterations_without_improvement: Option<usize>,
+    target_value: Option<f64>,
+    segment_size: usize,
+    reaction_factor: f64,
+    scores: OperatorScores,
+    reset_after: Option<usize>,
 }
 
 impl SolverOptions {
     #[must_use]
     pub fn new(max_iterations: usize) -> Self {
-        SolverOptions { max_iterations }
+        SolverOptions {
+            max_iterations,
+            ..SolverOptions::default()
+        }
+    }
+
+    /// Wall-clock time after which the solver stops. The current iteration is always completed.
+    #[must_use]
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Number of iterations without a new best solution after which the solver stops.
+    #[must_use]
+    pub fn max_iterations_without_improvement(mut self, iterations: usize) -> Self {
+        self.max_iterations_without_improvement = Some(iterations);
+        self
+    }
+
+    /// Solution value at or below which the solver stops.
+    #[must_use]
+    pub fn target_value(mut self, value: f64) -> Self {
+        self.target_value = Some(value);
+        self
+    }
+
+    /// Number of iterations after which operator weights are updated from their scores.
+    #[must_use]
+    pub fn segment_size(mut self, iterations: usize) -> Self {
+        self.segment_size = iterations;
+        self
+    }
+
+    /// Share of an operator's weight replaced by its average score at the end of a segment,
+    /// between zero (weights never change) and one (weights only reflect the last segment).
+    #[must_use]
+    pub fn reaction_factor(mut self, factor: f64) -> Self {
+        self.reaction_factor = factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Number of iterations without a new best solution after which the search is reset to the
+    /// best solution. `None` never resets.
+    #[must_use]
+    pub fn reset_after(mut self, iterations: Option<usize>) -> Self {
+        self.reset_after = iterations;
+        self
+    }
+
+    #[must_use]
+    pub fn scores(mut self, scores: OperatorScores) -> Self {
+        self.scores = scores;
+        self
     }
 }
 
@@ -141,13 +473,48 @@ impl Default for SolverOptions {
     fn default() -> Self {
         SolverOptions {
             max_iterations: 100,
+            time_limit: None,
+            max_iterations_without_improvement: None,
+            target_value: None,
+            segment_size: 100,
+            reaction_factor: 0.1,
+            scores: OperatorScores::default(),
+            reset_after: Some(50),
+        }
+    }
+}
+
+/// Scores rewarded to the selected operators depending on the solution they produce.
+pub struct OperatorScores {
+    /// The solution is a new best solution.
+    pub best: f64,
+    /// The solution improves the current solution.
+    pub improved: f64,
+    /// The solution is accepted without improving the current solution.
+    pub accepted: f64,
+}
+
+impl Default for OperatorScores {
+    fn default() -> Self {
+        OperatorScores {
+            best: 33.0,
+            improved: 9.0,
+            accepted: 13.0,
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
     use super::*;
+    use crate::constraint::{CapacityConstraint, VehicleCompatibilityConstraint};
+    use crate::model::{
+        CompatibilityAttribute, DEFAULT_UNPLANNED_PENALTY, DistanceMatrix, Location, ModelBuilder,
+        Stop, StopBuilder, VehicleBuilder,
+    };
+    use crate::objective::{TravelDistanceObjective, UnplannedObjective};
+    use crate::operator::{Amount, OperatorParameters, RandomRepairOperator};
 
     #[test]
     fn test_solver() {
@@ -155,11 +522,242 @@ mod tests {
         let solver = SolverBuilder::default().options(options).build();
 
         assert_eq!(solver.options.max_iterations, 10);
-        assert_eq!(solver.operators().len(), 2);
+        assert_eq!(solver.operators().len(), 3);
         assert_eq!(solver.iteration_count, 0);
         assert!(solver.solution.is_none());
-        assert_eq!(solver.model.objectives().len(), 1);
-        assert_eq!(solver.model.constraints().len(), 1);
+        assert_eq!(solver.model.objectives().len(), 3);
+        assert_eq!(solver.model.constraints().len(), 4);
+        assert_eq!(solver.acceptance().name(), "simulated_annealing");
+    }
+
+    #[test]
+    fn test_solver_solve() {
+        let stop = |id| Stop::new(id, Location::new(id, 0.0, 0.0), vec![1.0]);
+        let model = ModelBuilder::new()
+            .stop(stop(1))
+            .stop(stop(2))
+            .stop(stop(3))
+            .vehicle(
+                VehicleBuilder::new(0, vec![2.0])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .build(),
+            )
+            .vehicle(
+                VehicleBuilder::new(1, vec![2.0])
+                    .start_location(Location::new(0, 0.0, 0.0))
+                    .build(),
+            )
+            .distance_matrix(DistanceMatrix::new(vec![
+                vec![0.0, 1.0, 2.0, 3.0],
+                vec![1.0, 0.0, 1.0, 2.0],
+                vec![2.0, 1.0, 0.0, 1.0],
+                vec![3.0, 2.0, 1.0, 0.0],
+            ]))
+            .objective(UnplannedObjective)
+            .objective(TravelDistanceObjective)
+            .constraint(CapacityConstraint)
+            .build();
+        let solver = SolverBuilder::new()
+            .model(model)
+            .operator(DestroyOperator::default())
+            .operator(RepairOperator::default())
+            .options(SolverOptions::new(20))
+            .build();
+
+        let solution = solver.solve().unwrap();
+        assert!(solution.unplanned().is_empty());
+        assert_eq!(solution.value(), 4.0);
+    }
+
+    #[test]
+    fn test_operator_weights() {
+        let operators = SolverBuilder::new()
+            .operator(DestroyOperator::default())
+            .operator(RepairOperator::default())
+            .operator(RandomRepairOperator::default())
+            .build()
+            .operators;
+        let mut weights = OperatorWeights::new(&operators);
+        let mut random = Random::seed(7);
+        for _ in 0..20 {
+            assert_eq!(
+                weights.select(&operators, OperatorKind::Destroy, &mut random),
+                Some(0)
+            );
+            let repair = weights.select(&operators, OperatorKind::Repair, &mut random);
+            assert!(matches!(repair, Some(1 | 2)));
+        }
+
+        weights.reward(1, 10.0);
+        weights.reward(1, 0.0);
+        weights.update(0.5);
+        assert_eq!(weights.weights, vec![1.0, 3.0, 1.0]);
+        assert_eq!(weights.uses, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_solver_incompatible_initial_solution() {
+        let model = |constraint: bool| {
+            let builder = ModelBuilder::new()
+                .stop(
+                    StopBuilder::new(1, Location::new(1, 0.0, 0.0), vec![])
+                        .compatibility_attribute(CompatibilityAttribute::new(
+                            0,
+                            String::from("refrigerated"),
+                        ))
+                        .build(),
+                )
+                .vehicle(VehicleBuilder::new(0, vec![]).build())
+                .objective(UnplannedObjective);
+            if constraint {
+                builder.constraint(VehicleCompatibilityConstraint::default())
+            } else {
+                builder
+            }
+            .build()
+        };
+        let mut plan = Plan::new();
+        plan.insert(0, 0, vec![0]);
+        let initial = Solution::new().plan(&model(false), &plan).unwrap();
+        let solution = SolverBuilder::new()
+            .model(model(true))
+            .solution(initial)
+            .options(SolverOptions::new(0))
+            .build()
+            .solve()
+            .unwrap();
+        assert_eq!(solution.unplanned(), &[0]);
+    }
+
+    #[test]
+    fn test_solver_termination() {
+        let model = || {
+            ModelBuilder::new()
+                .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+                .vehicle(VehicleBuilder::new(0, vec![]).build())
+                .objective(UnplannedObjective)
+                .build()
+        };
+        let solve = |options: SolverOptions| {
+            let solution = SolverBuilder::new()
+                .model(model())
+                .operator(DestroyOperator::default())
+                .operator(RepairOperator::default())
+                .options(options)
+                .build()
+                .solve()
+                .unwrap();
+            let statistics = solution.statistics().unwrap();
+            (statistics.stop_reason(), statistics.iterations())
+        };
+
+        assert_eq!(solve(SolverOptions::new(5)), (StopReason::MaxIterations, 5));
+        assert_eq!(
+            solve(SolverOptions::new(5).target_value(0.0)),
+            (StopReason::TargetValue, 1)
+        );
+        assert_eq!(
+            solve(SolverOptions::new(100).max_iterations_without_improvement(3)),
+            (StopReason::Stagnation, 4)
+        );
+        assert_eq!(
+            solve(SolverOptions::new(usize::MAX).time_limit(Duration::ZERO)),
+            (StopReason::TimeLimit, 0)
+        );
+    }
+
+    #[test]
+    fn test_solver_cancellation() {
+        let model = || {
+            ModelBuilder::new()
+                .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+                .vehicle(VehicleBuilder::new(0, vec![]).build())
+                .objective(UnplannedObjective)
+                .build()
+        };
+        let token = CancellationToken::new();
+        let solver = SolverBuilder::new()
+            .model(model())
+            .operator(DestroyOperator::default())
+            .operator(RepairOperator::default())
+            .cancellation_token(token.clone())
+            .options(SolverOptions::new(usize::MAX))
+            .build();
+        token.cancel();
+        let solution = solver.solve().unwrap();
+        let statistics = solution.statistics().unwrap();
+        assert_eq!(statistics.stop_reason(), StopReason::Cancelled);
+        assert_eq!(statistics.iterations(), 0);
+
+        let solver = SolverBuilder::new()
+            .model(model())
+            .operator(DestroyOperator::default())
+            .operator(RepairOperator::default())
+            .options(SolverOptions::new(usize::MAX))
+            .build();
+        let token = solver.cancellation_token();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        });
+        let solution = solver.solve().unwrap();
+        handle.join().unwrap();
+        assert!(solution.unplanned().is_empty());
+        assert_eq!(
+            solution.statistics().map(SolutionStatistics::stop_reason),
+            Some(StopReason::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_solver_reset() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+            .objective(UnplannedObjective)
+            .build();
+        let mut solver = SolverBuilder::new()
+            .model(model)
+            .operator(ResetOperator {
+                parameters: OperatorParameters::new(Amount::Count(0), 1.0),
+            })
+            .options(SolverOptions::new(10).reset_after(Some(3)))
+            .build();
+        solver.best = Solution::new().plan(&solver.model, &Plan::new());
+        solver.solution = Some(Solution::new());
+        solver.weights = OperatorWeights::new(&solver.operators);
+        assert!(!solver.is_stagnant());
+        solver.iterations_without_improvement = 3;
+        assert!(solver.is_stagnant());
+        assert_eq!(solver.solution().map(Solution::value), Some(0.0));
+        solver.reset();
+        assert_eq!(
+            solver.solution().map(Solution::value),
+            Some(DEFAULT_UNPLANNED_PENALTY)
+        );
+        solver.iterations_without_improvement = 4;
+        assert!(!solver.is_stagnant());
+    }
+
+    #[test]
+    fn test_solver_reset_without_operator() {
+        let model = ModelBuilder::new()
+            .stop(Stop::new(1, Location::new(1, 0.0, 0.0), vec![]))
+            .objective(UnplannedObjective)
+            .build();
+        let mut solver = SolverBuilder::new()
+            .model(model)
+            .operator(DestroyOperator::default())
+            .options(SolverOptions::new(10).reset_after(Some(3)))
+            .build();
+        solver.best = Solution::new().plan(&solver.model, &Plan::new());
+        solver.solution = Some(Solution::new());
+        solver.weights = OperatorWeights::new(&solver.operators);
+        assert_eq!(solver.solution().map(Solution::value), Some(0.0));
+        solver.reset();
+        assert_eq!(
+            solver.solution().map(Solution::value),
+            Some(DEFAULT_UNPLANNED_PENALTY)
+        );
     }
 
     #[test]